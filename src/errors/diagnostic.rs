@@ -0,0 +1,112 @@
+use std::error::Error;
+use std::fmt::Write;
+use std::ops::Range;
+use serde::Serialize;
+
+/// The bytes `start..end` of the source an error is about. An empty span points between two characters, e.g. at
+/// the end of the line for a missing parenthesis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    #[must_use]
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Span::new(range.start, range.end)
+    }
+}
+
+/// An error that can be rendered against the source it comes from, see [`render`].
+pub trait Diagnostic: Error {
+    /// A stable identifier of the kind of error, such as `E0101`.
+    fn code(&self) -> &'static str;
+
+    /// The part of the source the error is about. Errors without one are about the whole source.
+    fn span(&self) -> Option<Span>;
+
+    /// A note about how to fix the error.
+    fn help(&self) -> Option<&str>;
+}
+
+/// Renders the diagnostic with the line it is about, the faulty part being underlined. The lexer and the parser
+/// stop at the first error of a statement, so there is one diagnostic per failing statement. The result has no
+/// trailing newline.
+///
+/// ```text
+/// error[E0103]: Couldn't find symbol x
+///  --> 1:5
+///   |
+/// 1 | 1 + x
+///   |     ^
+///   = help: define it first, e.g. x = 1
+/// ```
+#[must_use]
+pub fn render(source: &str, diagnostic: &dyn Diagnostic) -> String {
+    render_in("", source, diagnostic)
+}
+
+/// Renders the diagnostic like [`render`], the source coming from `origin`, such as a file name, which is shown
+/// before the position: ` --> budget.calc:3:7`.
+#[must_use]
+pub fn render_in(origin: &str, source: &str, diagnostic: &dyn Diagnostic) -> String {
+    let mut rendered = String::new();
+    render_one(&mut rendered, origin, source, diagnostic);
+    rendered.truncate(rendered.trim_end().len());
+    rendered
+}
+
+/// The line and column, both starting at 1, of the byte `offset` of the source. Columns count characters.
+#[must_use]
+pub fn position(source: &str, offset: usize) -> (usize, usize) {
+    let offset = floor_char_boundary(source, offset);
+    let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+    (source[..line_start].matches('\n').count() + 1, source[line_start..offset].chars().count() + 1)
+}
+
+fn render_one(rendered: &mut String, origin: &str, source: &str, diagnostic: &dyn Diagnostic) {
+    let span = diagnostic.span().unwrap_or_else(|| {
+        let start = source.len() - source.trim_start().len();
+        Span::new(start, source.trim_end().len().max(start))
+    });
+    let start = floor_char_boundary(source, span.start);
+    let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = source[start..].find('\n').map_or(source.len(), |index| start + index);
+    let line = source[line_start..line_end].trim_end_matches('\r');
+    let (line_number, column) = position(source, start);
+    // Spans going over several lines are only underlined up to the end of their first line.
+    let end = floor_char_boundary(source, span.end.clamp(start, line_start + line.len()));
+    let carets = source[start..end].chars().count().max(1);
+    let gutter = " ".repeat(line_number.to_string().len());
+
+    let _ = writeln!(rendered, "error[{}]: {diagnostic}", diagnostic.code());
+    let origin = if origin.is_empty() { String::new() } else { format!("{origin}:") };
+    let _ = writeln!(rendered, "{gutter}--> {origin}{line_number}:{column}");
+    let _ = writeln!(rendered, "{gutter} |");
+    let _ = writeln!(rendered, "{line_number} | {line}");
+    let _ = writeln!(rendered, "{gutter} | {}{}", " ".repeat(column - 1), "^".repeat(carets));
+    if let Some(help) = diagnostic.help() {
+        let _ = writeln!(rendered, "{gutter} = help: {help}");
+    }
+}
+
+// Spans come from the lexer so they should fall on character boundaries, but rendering must never panic.
+fn floor_char_boundary(source: &str, index: usize) -> usize {
+    let mut index = index.min(source.len());
+    while !source.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
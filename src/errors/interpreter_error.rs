@@ -1,24 +1,193 @@
 use std::error::Error;
 use std::fmt;
+use serde::Serialize;
+use crate::errors::diagnostic::{Diagnostic, Span};
+use crate::expressions::units::Dimension;
 
+/// What went wrong while evaluating an AST. Values are kept as they are displayed, e.g. `3 m`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InterpreterErrorKind {
+    /// Assigning a variable in terms of itself, `cycle` going from the variable back to it, e.g. `[a, b, a]`.
+    CyclicDefinition { cycle: Vec<String> },
+    RedefinedBuiltin { name: String },
+    RedefinedConstant { name: String },
+    DivisionByZero,
+    NegativePowerOfZero,
+    UndefinedVariable { name: String },
+    UndefinedFunction { name: String },
+    /// `expected` describes the accepted number of arguments, e.g. `at least 1 argument(s)`.
+    ArityMismatch { function: String, expected: String, found: usize },
+    /// A function called outside of its domain, `domain` describing it, e.g. `for positive numbers`.
+    OutOfDomain { function: String, domain: String, found: String },
+    /// A function whose result isn't a number, such as `sqrt(x)` for `2 m`.
+    Undefined { function: String, arguments: Vec<String> },
+    NotReal { base: String, exponent: String },
+    Overflow { base: String, exponent: String },
+    CallDepthExceeded { function: String, max_depth: usize },
+    /// Adding or subtracting (`operation`) quantities of different dimensions.
+    DimensionMismatch { operation: String, left: String, right: String, expected: Dimension, found: Dimension },
+    InvalidConversion { value: String, unit: String, from: Dimension, to: Dimension },
+    /// Raising a quantity to a power that doesn't give a unit (`m^0.5`), or anything to the power of a quantity.
+    InvalidUnitPower { base: String, exponent: String },
+    NothingToEvaluate,
+}
+
+impl InterpreterErrorKind {
+    /// A stable identifier of the kind of error, see [`Diagnostic::code`].
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            InterpreterErrorKind::CyclicDefinition { .. } => "E0201",
+            InterpreterErrorKind::RedefinedBuiltin { .. } | InterpreterErrorKind::RedefinedConstant { .. } => "E0202",
+            InterpreterErrorKind::DivisionByZero | InterpreterErrorKind::NegativePowerOfZero => "E0203",
+            InterpreterErrorKind::UndefinedVariable { .. } => "E0204",
+            InterpreterErrorKind::UndefinedFunction { .. } => "E0205",
+            InterpreterErrorKind::ArityMismatch { .. } => "E0206",
+            InterpreterErrorKind::OutOfDomain { .. }
+            | InterpreterErrorKind::Undefined { .. }
+            | InterpreterErrorKind::NotReal { .. } => "E0207",
+            InterpreterErrorKind::CallDepthExceeded { .. } => "E0208",
+            InterpreterErrorKind::DimensionMismatch { .. }
+            | InterpreterErrorKind::InvalidConversion { .. }
+            | InterpreterErrorKind::InvalidUnitPower { .. } => "E0209",
+            InterpreterErrorKind::Overflow { .. } => "E0210",
+            InterpreterErrorKind::NothingToEvaluate => "E0211",
+        }
+    }
+}
+
+impl fmt::Display for InterpreterErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpreterErrorKind::CyclicDefinition { cycle } => {
+                write!(f, "Illegal assignation: cyclic definition {}", cycle.join(" -> "))
+            }
+            InterpreterErrorKind::RedefinedBuiltin { name } => {
+                write!(f, "{name} is a builtin function and cannot be redefined")
+            }
+            InterpreterErrorKind::RedefinedConstant { name } => {
+                write!(f, "{name} is a protected constant and cannot be redefined")
+            }
+            InterpreterErrorKind::DivisionByZero => write!(f, "Cannot divide by 0."),
+            InterpreterErrorKind::NegativePowerOfZero => write!(f, "Cannot raise 0 to a negative power."),
+            InterpreterErrorKind::UndefinedVariable { name } => write!(f, "Variable {name} not found"),
+            InterpreterErrorKind::UndefinedFunction { name } => write!(f, "Function {name} not found"),
+            InterpreterErrorKind::ArityMismatch { function, expected, found } => {
+                write!(f, "{function} expects {expected}, got {found}")
+            }
+            InterpreterErrorKind::OutOfDomain { function, domain, found } => {
+                write!(f, "{function} is only defined {domain}, got {found}")
+            }
+            InterpreterErrorKind::Undefined { function, arguments } => {
+                write!(f, "{function} is not defined for {}", arguments.join(", "))
+            }
+            InterpreterErrorKind::NotReal { base, exponent } => {
+                write!(f, "{}^{exponent} is not a real number.", power_base(base))
+            }
+            InterpreterErrorKind::Overflow { base, exponent } => {
+                write!(f, "{}^{exponent} is too large to be computed.", power_base(base))
+            }
+            InterpreterErrorKind::CallDepthExceeded { function, max_depth } => {
+                write!(f, "Maximum call depth of {max_depth} exceeded while calling {function}")
+            }
+            InterpreterErrorKind::DimensionMismatch { operation, left, right, expected, found } => {
+                write!(f, "Cannot {operation} {left} and {right}: {expected} is not {found}")
+            }
+            InterpreterErrorKind::InvalidConversion { value, unit, from, to } => {
+                write!(f, "Cannot convert {value} to {unit}: {from} is not {to}")
+            }
+            InterpreterErrorKind::InvalidUnitPower { base, exponent } => {
+                write!(f, "Cannot raise {base} to the power {exponent}: the result wouldn't have a valid unit.")
+            }
+            InterpreterErrorKind::NothingToEvaluate => write!(f, "Nothing to evaluate"),
+        }
+    }
+}
+
+// The base of a power as it would be written: `(-2)^0.5` rather than `-2^0.5`, which is `-(2^0.5)`, and likewise for
+// fractions, complex numbers and quantities.
+fn power_base(base: &str) -> String {
+    if base.contains(['-', '+', '/', ' ', '∠']) {
+        format!("({base})")
+    } else {
+        base.to_string()
+    }
+}
+
+/// Errors raised while interpreting point at the division, power, call or variable that failed. The other ones,
+/// such as adding quantities of different dimensions, have no span unless the caller gives them one, and are
+/// rendered as being about the whole statement.
 #[derive(Debug)]
 pub struct InterpreterError {
-    message: String,
+    // Boxed so that results stay small on the deeply recursive evaluation path
+    kind: Box<InterpreterErrorKind>,
+    span: Option<Span>,
+    help: Option<String>,
 }
 
 impl InterpreterError {
     #[must_use]
-    pub fn new(message: String) -> InterpreterError {
+    pub fn new(kind: InterpreterErrorKind) -> InterpreterError {
+        InterpreterError {
+            kind: Box::new(kind),
+            span: None,
+            help: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_span(self, span: Span) -> InterpreterError {
+        InterpreterError {
+            span: Some(span),
+            ..self
+        }
+    }
+
+    // The same error, about no part of any line.
+    #[must_use]
+    pub(crate) fn without_span(self) -> InterpreterError {
+        InterpreterError { span: None, ..self }
+    }
+
+    #[must_use]
+    pub fn with_help(self, help: impl Into<String>) -> InterpreterError {
         InterpreterError {
-            message
+            help: Some(help.into()),
+            ..self
         }
     }
+
+    #[must_use]
+    pub fn kind(&self) -> &InterpreterErrorKind {
+        &self.kind
+    }
+}
+
+impl From<InterpreterErrorKind> for InterpreterError {
+    fn from(kind: InterpreterErrorKind) -> Self {
+        InterpreterError::new(kind)
+    }
 }
 
 impl Error for InterpreterError {}
 
+impl Diagnostic for InterpreterError {
+    fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+}
+
 impl fmt::Display for InterpreterError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "MyError: {}", self.message)
+        write!(f, "{}", self.kind)
     }
-}
\ No newline at end of file
+}
@@ -1,24 +1,92 @@
 use std::error::Error;
 use std::fmt;
+use serde::Serialize;
+use crate::errors::diagnostic::{Diagnostic, Span};
+
+/// What went wrong while splitting a line into tokens.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LexerErrorKind {
+    /// A character that can't start any token, such as `$`.
+    UnknownToken { found: char },
+    /// A conversion to something that isn't a unit, such as `1 km to parsec`.
+    UnknownUnit { unit: String, reason: String },
+}
+
+impl LexerErrorKind {
+    /// A stable identifier of the kind of error, see [`Diagnostic::code`].
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexerErrorKind::UnknownToken { .. } => "E0001",
+            LexerErrorKind::UnknownUnit { .. } => "E0002",
+        }
+    }
+}
+
+impl fmt::Display for LexerErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexerErrorKind::UnknownToken { found } => write!(f, "unknown token '{found}'"),
+            LexerErrorKind::UnknownUnit { unit, reason } => write!(f, "invalid unit '{unit}': {reason}"),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct LexerError {
-    message: String,
+    kind: LexerErrorKind,
+    span: Span,
+    help: Option<String>,
 }
 
 impl LexerError {
     #[must_use]
-    pub fn new(message: String) -> LexerError {
+    pub fn new(kind: LexerErrorKind, span: Span) -> LexerError {
         LexerError {
-            message,
+            kind,
+            span,
+            help: None,
         }
     }
+
+    #[must_use]
+    pub fn with_span(self, span: Span) -> LexerError {
+        LexerError { span, ..self }
+    }
+
+    #[must_use]
+    pub fn with_help(self, help: impl Into<String>) -> LexerError {
+        LexerError {
+            help: Some(help.into()),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> &LexerErrorKind {
+        &self.kind
+    }
 }
 
 impl Error for LexerError {}
 
+impl Diagnostic for LexerError {
+    fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+
+    fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+}
+
 impl fmt::Display for LexerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "MyError: {}", self.message)
+        write!(f, "{}", self.kind)
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1,91 @@
+use std::fmt;
+use crate::errors::diagnostic::{Diagnostic, Span};
+use crate::errors::interpreter_error::InterpreterError;
+use crate::errors::lexer_error::LexerError;
+use crate::errors::parser_error::SyntaxError;
+
+pub mod diagnostic;
 pub mod lexer_error;
 pub mod parser_error;
 pub mod interpreter_error;
+
+/// Any error that can happen while evaluating a line, so that lexing, parsing and interpreting can be chained
+/// with `?`.
+#[derive(Debug)]
+pub enum Error {
+    Lexer(LexerError),
+    Syntax(SyntaxError),
+    Interpreter(InterpreterError),
+}
+
+impl Error {
+    /// Moves the error `offset` bytes further, for errors about a part of a bigger source, such as a line of a file.
+    #[must_use]
+    pub fn offset(self, offset: usize) -> Error {
+        let Some(span) = self.span() else {
+            return self;
+        };
+        let span = Span::new(span.start + offset, span.end + offset);
+        match self {
+            Error::Lexer(error) => Error::Lexer(error.with_span(span)),
+            Error::Syntax(error) => Error::Syntax(error.with_span(span)),
+            Error::Interpreter(error) => Error::Interpreter(error.with_span(span)),
+        }
+    }
+
+    fn diagnostic(&self) -> &dyn Diagnostic {
+        match self {
+            Error::Lexer(error) => error,
+            Error::Syntax(error) => error,
+            Error::Interpreter(error) => error,
+        }
+    }
+}
+
+impl From<LexerError> for Error {
+    fn from(error: LexerError) -> Self {
+        Error::Lexer(error)
+    }
+}
+
+impl From<SyntaxError> for Error {
+    fn from(error: SyntaxError) -> Self {
+        Error::Syntax(error)
+    }
+}
+
+impl From<InterpreterError> for Error {
+    fn from(error: InterpreterError) -> Self {
+        Error::Interpreter(error)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Lexer(error) => Some(error),
+            Error::Syntax(error) => Some(error),
+            Error::Interpreter(error) => Some(error),
+        }
+    }
+}
+
+impl Diagnostic for Error {
+    fn code(&self) -> &'static str {
+        self.diagnostic().code()
+    }
+
+    fn span(&self) -> Option<Span> {
+        self.diagnostic().span()
+    }
+
+    fn help(&self) -> Option<&str> {
+        self.diagnostic().help()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.diagnostic())
+    }
+}
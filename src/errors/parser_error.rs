@@ -1,24 +1,105 @@
 use std::error::Error;
 use std::fmt;
+use serde::Serialize;
+use crate::errors::diagnostic::{Diagnostic, Span};
+
+/// What went wrong while building the AST of a line.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyntaxErrorKind {
+    /// `expected` describes what would have been valid, e.g. `')'` or `a value`.
+    UnexpectedToken { expected: String, found: String },
+    /// The line ended while `expected` was still missing.
+    UnexpectedEnd { expected: String },
+    /// A name that is neither a variable, a function, a parameter nor a constant.
+    UnknownSymbol { name: String },
+    InvalidLiteral { literal: String, reason: String },
+    InvalidUnit { unit: String, reason: String },
+    DuplicateParameter { name: String },
+}
+
+impl SyntaxErrorKind {
+    /// A stable identifier of the kind of error, see [`Diagnostic::code`].
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            SyntaxErrorKind::UnexpectedToken { .. } => "E0101",
+            SyntaxErrorKind::UnexpectedEnd { .. } => "E0102",
+            SyntaxErrorKind::UnknownSymbol { .. } => "E0103",
+            SyntaxErrorKind::InvalidLiteral { .. } => "E0104",
+            SyntaxErrorKind::InvalidUnit { .. } => "E0105",
+            SyntaxErrorKind::DuplicateParameter { .. } => "E0106",
+        }
+    }
+}
+
+impl fmt::Display for SyntaxErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyntaxErrorKind::UnexpectedToken { expected, found } => write!(f, "Expected {expected}, got '{found}'"),
+            SyntaxErrorKind::UnexpectedEnd { expected } => write!(f, "Expected {expected}, got nothing"),
+            SyntaxErrorKind::UnknownSymbol { name } => write!(f, "Couldn't find symbol {name}"),
+            SyntaxErrorKind::InvalidLiteral { literal, reason } => write!(f, "Couldn't parse {literal} to a number: {reason}"),
+            SyntaxErrorKind::InvalidUnit { unit, reason } => write!(f, "Couldn't parse {unit} to a unit: {reason}"),
+            SyntaxErrorKind::DuplicateParameter { name } => write!(f, "Duplicate parameter {name}"),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct SyntaxError {
-    message: String,
+    kind: SyntaxErrorKind,
+    span: Span,
+    help: Option<String>,
 }
 
 impl SyntaxError {
     #[must_use]
-    pub fn new(message: String) -> SyntaxError {
+    pub fn new(kind: SyntaxErrorKind, span: Span) -> SyntaxError {
         SyntaxError {
-            message,
+            kind,
+            span,
+            help: None,
         }
     }
+
+    #[must_use]
+    pub fn with_span(self, span: Span) -> SyntaxError {
+        SyntaxError { span, ..self }
+    }
+
+    #[must_use]
+    pub fn with_help(self, help: impl Into<String>) -> SyntaxError {
+        SyntaxError {
+            help: Some(help.into()),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> &SyntaxErrorKind {
+        &self.kind
+    }
 }
 
 impl Error for SyntaxError {}
 
+impl Diagnostic for SyntaxError {
+    fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+
+    fn help(&self) -> Option<&str> {
+        self.help.as_deref()
+    }
+}
+
 impl fmt::Display for SyntaxError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "MyError: {}", self.message)
+        write!(f, "{}", self.kind)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+use bigdecimal::BigDecimal;
+use num_complex::Complex64;
+use crate::errors::interpreter_error::{InterpreterError, InterpreterErrorKind};
+use crate::expressions::interpreter::decimal::DecimalContext;
+use crate::expressions::interpreter::number::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    #[must_use]
+    pub fn accepts(&self, argument_count: usize) -> bool {
+        match self {
+            Arity::Exactly(n) => argument_count == *n,
+            Arity::AtLeast(n) => argument_count >= *n,
+        }
+    }
+}
+
+pub struct Builtin {
+    pub name: &'static str,
+    pub signature: &'static str,
+    pub arity: Arity,
+    function: fn(&[Number]) -> Result<Number, InterpreterError>,
+}
+
+impl Builtin {
+    /// # Errors
+    ///
+    /// Will return an error if the number of arguments doesn't match the function arity,
+    /// or if the arguments are outside of the function domain.
+    pub fn call(&self, arguments: &[Number]) -> Result<Number, InterpreterError> {
+        if !self.arity.accepts(arguments.len()) {
+            return Err(InterpreterErrorKind::ArityMismatch {
+                function: self.signature.into(),
+                expected: match self.arity {
+                    Arity::Exactly(1) => String::from("1 argument"),
+                    Arity::Exactly(n) => format!("{n} arguments"),
+                    Arity::AtLeast(n) => format!("at least {n} argument(s)"),
+                },
+                found: arguments.len(),
+            }
+            .into());
+        }
+        let result = (self.function)(arguments)?;
+        if result.is_nan() {
+            Err(InterpreterErrorKind::Undefined {
+                function: self.signature.into(),
+                arguments: arguments.iter().map(ToString::to_string).collect(),
+            }
+            .into())
+        } else {
+            Ok(result)
+        }
+    }
+}
+
+fn positive<'a>(name: &str, value: &'a Number) -> Result<&'a Number, InterpreterError> {
+    if value.is_negative() || value.is_zero() {
+        Err(InterpreterErrorKind::OutOfDomain {
+            function: name.into(),
+            domain: String::from("for positive numbers"),
+            found: value.to_string(),
+        }
+        .into())
+    } else {
+        Ok(value)
+    }
+}
+
+// Most functions have irrational results, so they are computed with floats unless decimals are used. A decimal
+// function returning `None` gives NaN, which is reported as being outside of the function domain.
+fn irrational(
+    value: &Number,
+    float: fn(f64) -> f64,
+    decimal: fn(&DecimalContext, &BigDecimal) -> Option<BigDecimal>,
+    complex: fn(Complex64) -> Complex64,
+) -> Number {
+    match value {
+        Number::Decimal(value, context) => decimal(context, value)
+            .map_or(Number::Float(f64::NAN), |result| Number::Decimal(result, *context)),
+        Number::Complex(value) => Number::complex(complex(*value)),
+        _ => Number::Float(float(value.to_f64())),
+    }
+}
+
+fn irrational2(
+    left: &Number,
+    right: &Number,
+    float: fn(f64, f64) -> f64,
+    decimal: fn(&DecimalContext, &BigDecimal, &BigDecimal) -> Option<BigDecimal>,
+) -> Number {
+    if let Some(context) = left.decimal_context().or_else(|| right.decimal_context()) {
+        if let (Some(left), Some(right)) = (left.to_decimal(&context), right.to_decimal(&context)) {
+            return decimal(&context, &left, &right)
+                .map_or(Number::Float(f64::NAN), |result| Number::Decimal(result, context));
+        }
+    }
+    Number::Float(float(left.to_f64(), right.to_f64()))
+}
+
+// Arity is checked before calling the function, so indexing the arguments can't panic.
+pub const BUILTINS: &[Builtin] = &[
+    Builtin { name: "sqrt", signature: "sqrt(x)", arity: Arity::Exactly(1), function: |args| Ok(args[0].sqrt()) },
+    Builtin { name: "abs", signature: "abs(x)", arity: Arity::Exactly(1), function: |args| Ok(args[0].abs()) },
+    Builtin { name: "re", signature: "re(z)", arity: Arity::Exactly(1), function: |args| Ok(args[0].real_part()) },
+    Builtin { name: "im", signature: "im(z)", arity: Arity::Exactly(1), function: |args| Ok(args[0].imaginary_part()) },
+    Builtin { name: "conj", signature: "conj(z)", arity: Arity::Exactly(1), function: |args| Ok(args[0].conjugate()) },
+    Builtin { name: "arg", signature: "arg(z)", arity: Arity::Exactly(1), function: |args| Ok(args[0].argument()) },
+    Builtin { name: "sin", signature: "sin(x)", arity: Arity::Exactly(1), function: |args| Ok(irrational(&args[0], f64::sin, DecimalContext::sin, Complex64::sin)) },
+    Builtin { name: "cos", signature: "cos(x)", arity: Arity::Exactly(1), function: |args| Ok(irrational(&args[0], f64::cos, DecimalContext::cos, Complex64::cos)) },
+    Builtin { name: "tan", signature: "tan(x)", arity: Arity::Exactly(1), function: |args| Ok(irrational(&args[0], f64::tan, DecimalContext::tan, Complex64::tan)) },
+    Builtin { name: "asin", signature: "asin(x)", arity: Arity::Exactly(1), function: |args| {
+        if args[0].is_complex() || (-1.0..=1.0).contains(&args[0].to_f64()) {
+            Ok(irrational(&args[0], f64::asin, DecimalContext::asin, Complex64::asin))
+        } else {
+            Err(InterpreterErrorKind::OutOfDomain { function: "asin".into(), domain: "between -1 and 1".into(), found: args[0].to_string() }.into())
+        }
+    } },
+    Builtin { name: "atan2", signature: "atan2(y, x)", arity: Arity::Exactly(2), function: |args| Ok(irrational2(&args[0], &args[1], f64::atan2, |context, y, x| Some(context.atan2(y, x)))) },
+    Builtin { name: "ln", signature: "ln(x)", arity: Arity::Exactly(1), function: |args| Ok(irrational(positive("ln", &args[0])?, f64::ln, DecimalContext::ln, Complex64::ln)) },
+    Builtin { name: "log10", signature: "log10(x)", arity: Arity::Exactly(1), function: |args| Ok(irrational(positive("log10", &args[0])?, f64::log10, |context, x| context.log(&BigDecimal::from(10), x), Complex64::log10)) },
+    Builtin { name: "log", signature: "log(base, x)", arity: Arity::Exactly(2), function: |args| {
+        let base = positive("log base", &args[0])?;
+        if (base.to_f64() - 1.0).abs() < f64::EPSILON {
+            return Err(InterpreterErrorKind::OutOfDomain { function: "log".into(), domain: "for bases other than 1".into(), found: base.to_string() }.into());
+        }
+        Ok(irrational2(base, positive("log", &args[1])?, |base, x| x.log(base), DecimalContext::log))
+    } },
+    Builtin { name: "exp", signature: "exp(x)", arity: Arity::Exactly(1), function: |args| Ok(irrational(&args[0], f64::exp, DecimalContext::exp, Complex64::exp)) },
+    Builtin { name: "floor", signature: "floor(x)", arity: Arity::Exactly(1), function: |args| Ok(args[0].floor()) },
+    Builtin { name: "ceil", signature: "ceil(x)", arity: Arity::Exactly(1), function: |args| Ok(args[0].ceil()) },
+    Builtin { name: "round", signature: "round(x)", arity: Arity::Exactly(1), function: |args| Ok(args[0].round()) },
+    Builtin { name: "min", signature: "min(x, ...)", arity: Arity::AtLeast(1), function: |args| Ok(extremum(args, Ordering::Less)) },
+    Builtin { name: "max", signature: "max(x, ...)", arity: Arity::AtLeast(1), function: |args| Ok(extremum(args, Ordering::Greater)) },
+];
+
+// Nothing compares to NaN, so a NaN argument makes the result NaN as well.
+fn extremum(arguments: &[Number], wanted: Ordering) -> Number {
+    arguments
+        .iter()
+        .skip(1)
+        .fold(arguments[0].clone(), |best, argument| match argument.partial_cmp(&best) {
+            Some(ordering) if ordering == wanted => argument.clone(),
+            Some(_) => best,
+            None => Number::Float(f64::NAN),
+        })
+}
+
+#[must_use]
+pub fn find(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|builtin| builtin.name == name)
+}
@@ -0,0 +1,391 @@
+use std::num::NonZeroU64;
+use bigdecimal::{BigDecimal, Context, One, Signed, Zero};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{Pow, ToPrimitive};
+
+pub use bigdecimal::RoundingMode;
+
+/// Significant digits used when no precision is given.
+pub const DEFAULT_PRECISION: u64 = 50;
+// Extra digits carried by intermediate results, so that rounding errors don't reach the displayed digits.
+const GUARD_DIGITS: u64 = 10;
+// Beyond this, exp(x) has more than ~10^12 digits before the decimal point, which isn't worth computing.
+const MAX_EXP_ARGUMENT: i64 = 1_000_000_000_000;
+// Beyond this order of magnitude, reducing sin(x) or cos(x) to [-pi, pi] needs hundreds more digits of pi than the
+// precision, which gets slow.
+const MAX_TRIGONOMETRIC_MAGNITUDE: i64 = 300;
+
+/// How decimal numbers are computed: every result is rounded to `precision` significant digits with the
+/// `rounding` mode. Functions such as `sqrt`, `ln` or `sin` are computed with a few extra digits before being
+/// rounded, so their results are correct to the last digit in all but pathological cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalContext {
+    precision: NonZeroU64,
+    rounding: RoundingMode,
+}
+
+impl Default for DecimalContext {
+    fn default() -> Self {
+        Self::new(NonZeroU64::new(DEFAULT_PRECISION).unwrap_or(NonZeroU64::MIN), RoundingMode::HalfEven)
+    }
+}
+
+impl DecimalContext {
+    #[must_use]
+    pub fn new(precision: NonZeroU64, rounding: RoundingMode) -> Self {
+        Self { precision, rounding }
+    }
+
+    #[must_use]
+    pub fn precision(&self) -> NonZeroU64 {
+        self.precision
+    }
+
+    #[must_use]
+    pub fn rounding(&self) -> RoundingMode {
+        self.rounding
+    }
+
+    #[must_use]
+    pub fn round(&self, value: &BigDecimal) -> BigDecimal {
+        Context::new(self.precision, self.rounding).round_decimal_ref(value).normalized()
+    }
+
+    // Carries `extra` more digits than this context, rounding half-way cases to even to avoid any bias.
+    fn working(&self, extra: u64) -> DecimalContext {
+        DecimalContext::new(self.precision.saturating_add(GUARD_DIGITS + extra), RoundingMode::HalfEven)
+    }
+
+    // Whether adding `term` to `sum` can't change its significant digits anymore.
+    fn negligible(&self, term: &BigDecimal, sum: &BigDecimal) -> bool {
+        term.is_zero()
+            || (!sum.is_zero()
+                && sum.order_of_magnitude() - term.order_of_magnitude() > i64::try_from(self.precision.get()).unwrap_or(i64::MAX))
+    }
+
+    #[must_use]
+    pub fn from_rational(&self, value: &BigRational) -> BigDecimal {
+        let (numerator, denominator) = (value.numer(), value.denom());
+        // Enough decimals for the truncated quotient to have more significant digits than the precision.
+        let scale = i64::try_from(self.precision.get()).unwrap_or(i64::MAX).saturating_add(
+            digit_count(denominator) - digit_count(numerator) + 2,
+        );
+        let (numerator, denominator) = if scale >= 0 {
+            (numerator * power_of_ten(scale.unsigned_abs()), denominator.clone())
+        } else {
+            (numerator.clone(), denominator * power_of_ten(scale.unsigned_abs()))
+        };
+        let quotient = &numerator / &denominator;
+        // A non zero remainder is kept as an extra digit, so that rounding knows the quotient isn't exact: 0.5 is a
+        // half-way case but 0.50000001 isn't.
+        let digits = if (&numerator % &denominator).is_zero() {
+            BigDecimal::new(quotient, scale)
+        } else {
+            let sticky = if value.is_negative() { -1 } else { 1 };
+            BigDecimal::new(quotient * 10 + sticky, scale + 1)
+        };
+        self.round(&digits)
+    }
+
+    #[must_use]
+    pub fn add(&self, left: &BigDecimal, right: &BigDecimal) -> BigDecimal {
+        self.round(&(left + right))
+    }
+
+    #[must_use]
+    pub fn sub(&self, left: &BigDecimal, right: &BigDecimal) -> BigDecimal {
+        self.round(&(left - right))
+    }
+
+    #[must_use]
+    pub fn mul(&self, left: &BigDecimal, right: &BigDecimal) -> BigDecimal {
+        self.round(&(left * right))
+    }
+
+    /// Dividing by zero panics, callers are expected to check for zero first.
+    #[must_use]
+    pub fn div(&self, left: &BigDecimal, right: &BigDecimal) -> BigDecimal {
+        self.from_rational(&(to_rational(left) / to_rational(right)))
+    }
+
+    /// Returns `None` if the result would be too large to be represented.
+    #[must_use]
+    pub fn powi(&self, base: &BigDecimal, exponent: i32) -> Option<BigDecimal> {
+        let magnitude = base.order_of_magnitude().abs().saturating_add(1).saturating_mul(i64::from(exponent).abs());
+        if magnitude > MAX_EXP_ARGUMENT {
+            return None;
+        }
+        let working = self.working(u64::from(exponent.unsigned_abs().checked_ilog10().unwrap_or(0) + 1));
+        let mut result = BigDecimal::one();
+        let mut square = base.clone();
+        let mut remaining = exponent.unsigned_abs();
+        while remaining > 0 {
+            if remaining % 2 == 1 {
+                result = working.mul(&result, &square);
+            }
+            square = working.mul(&square, &square);
+            remaining /= 2;
+        }
+        if exponent < 0 {
+            result = working.div(&BigDecimal::one(), &result);
+        }
+        Some(self.round(&result))
+    }
+
+    /// `base^exponent` for any exponent, as `base^n * exp(f * ln(base))` with `n` the integer part of the exponent
+    /// and `f` its fractional part. Returns `None` if the base is negative, or if the result would be too large to be
+    /// represented.
+    #[must_use]
+    pub fn powf(&self, base: &BigDecimal, exponent: &BigDecimal) -> Option<BigDecimal> {
+        if base.is_zero() {
+            return (!exponent.is_negative()).then(BigDecimal::zero);
+        }
+        let integer = exponent.with_scale_round(0, RoundingMode::Down);
+        // ln(base) has about as many digits before the decimal point as the order of magnitude of the base, and its
+        // error is multiplied by what remains of the exponent.
+        let logarithm_digits = u64::from((base.order_of_magnitude().unsigned_abs() + 1).ilog10() + 1);
+        let Some(integer) = integer.to_i32() else {
+            let working = self.working(exponent.order_of_magnitude().max(0).unsigned_abs() + logarithm_digits);
+            let product = working.mul(&working.ln(base)?, exponent);
+            return working.exp(&product).map(|power| self.round(&power));
+        };
+        let working = self.working(logarithm_digits + 1);
+        let fraction = exponent - BigDecimal::from(integer);
+        let fractional_power = working.exp(&working.mul(&working.ln(base)?, &fraction))?;
+        let integer_power = working.powi(base, integer)?;
+        Some(self.round(&working.mul(&integer_power, &fractional_power)))
+    }
+
+    /// Returns `None` for negative numbers.
+    #[must_use]
+    pub fn sqrt(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        let working = self.working(0);
+        value
+            .sqrt_with_context(&Context::new(working.precision, working.rounding))
+            .map(|root| self.round(&root))
+    }
+
+    /// Returns `None` if the result would be too large to be represented.
+    #[must_use]
+    pub fn exp(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        if value.abs() > MAX_EXP_ARGUMENT {
+            return None;
+        }
+        // exp(x) = exp(x / 2^k)^(2^k), with x / 2^k small enough for the series to converge quickly. Each squaring
+        // doubles the relative error, hence the extra digits.
+        let mut reduced = value.clone();
+        let mut halvings = 0;
+        while reduced.abs() > BigDecimal::new(BigInt::from(5), 1) {
+            reduced = reduced.half();
+            halvings += 1;
+        }
+        let working = self.working(halvings / 3 + 1);
+        let mut sum = BigDecimal::one();
+        let mut term = BigDecimal::one();
+        for n in 1_u32.. {
+            term = working.div(&working.mul(&term, &reduced), &BigDecimal::from(n));
+            if working.negligible(&term, &sum) {
+                break;
+            }
+            sum = working.add(&sum, &term);
+        }
+        for _ in 0..halvings {
+            sum = working.mul(&sum, &sum);
+        }
+        Some(self.round(&sum))
+    }
+
+    /// Returns `None` for numbers that aren't positive.
+    #[must_use]
+    pub fn ln(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        if !value.is_positive() {
+            return None;
+        }
+        // ln(m * 10^e) = ln(m) + e * ln(10), with 1 <= m < 10. The error on ln(10) is multiplied by e.
+        let exponent = value.order_of_magnitude();
+        let (digits, scale) = value.as_bigint_and_exponent();
+        let mantissa = BigDecimal::new(digits, scale + exponent);
+        let working = self.working(u64::from(exponent.unsigned_abs().checked_ilog10().unwrap_or(0) + 1));
+        let mut result = working.ln_reduced(&mantissa);
+        if exponent != 0 {
+            let ln_10 = working.ln_reduced(&BigDecimal::from(10));
+            result = working.add(&result, &working.mul(&ln_10, &BigDecimal::from(exponent)));
+        }
+        Some(self.round(&result))
+    }
+
+    /// The logarithm of `value` in the given `base`. Returns `None` if either isn't positive, or if the base is 1.
+    #[must_use]
+    pub fn log(&self, base: &BigDecimal, value: &BigDecimal) -> Option<BigDecimal> {
+        let working = self.working(0);
+        let base = working.ln(base).filter(|logarithm| !logarithm.is_zero())?;
+        Some(self.round(&working.div(&working.ln(value)?, &base)))
+    }
+
+    // ln(x) = 2^k * ln(x^(1/2^k)) and ln(x) = 2 * atanh((x - 1) / (x + 1)), whose series converges quickly once the
+    // square roots brought x close to 1. Only meant for reasonably sized positive numbers.
+    fn ln_reduced(&self, value: &BigDecimal) -> BigDecimal {
+        let working = self.working(3);
+        let close_to_one = BigDecimal::new(BigInt::from(11), 1);
+        let mut reduced = value.clone();
+        let mut square_roots = 0;
+        while reduced > close_to_one || reduced < close_to_one.inverse() {
+            reduced = working.sqrt(&reduced).unwrap_or_default();
+            square_roots += 1;
+        }
+        let one = BigDecimal::one();
+        let z = working.div(&working.sub(&reduced, &one), &working.add(&reduced, &one));
+        let z_squared = working.mul(&z, &z);
+        let mut power = z.clone();
+        let mut sum = z;
+        for n in (3_u32..).step_by(2) {
+            power = working.mul(&power, &z_squared);
+            let term = working.div(&power, &BigDecimal::from(n));
+            if working.negligible(&term, &sum) {
+                break;
+            }
+            sum = working.add(&sum, &term);
+        }
+        working.mul(&sum, &BigDecimal::from(2_u64 << square_roots))
+    }
+
+    #[must_use]
+    pub fn pi(&self) -> BigDecimal {
+        // Machin's formula: pi = 16 * atan(1/5) - 4 * atan(1/239).
+        let working = self.working(0);
+        let fifth = working.atan_series(&BigDecimal::new(BigInt::from(2), 1));
+        let two_hundred_thirty_ninth = working.atan_series(&working.div(&BigDecimal::one(), &BigDecimal::from(239)));
+        self.round(&working.sub(
+            &working.mul(&fifth, &BigDecimal::from(16)),
+            &working.mul(&two_hundred_thirty_ninth, &BigDecimal::from(4)),
+        ))
+    }
+
+    // atan(x) = x - x^3/3 + x^5/5 - ..., which converges quickly for small values of x.
+    fn atan_series(&self, value: &BigDecimal) -> BigDecimal {
+        let x_squared = self.mul(value, value);
+        let mut power = value.clone();
+        let mut sum = value.clone();
+        for n in (3_u32..).step_by(2) {
+            power = -self.mul(&power, &x_squared);
+            let term = self.div(&power, &BigDecimal::from(n));
+            if self.negligible(&term, &sum) {
+                break;
+            }
+            sum = self.add(&sum, &term);
+        }
+        sum
+    }
+
+    #[must_use]
+    pub fn atan(&self, value: &BigDecimal) -> BigDecimal {
+        let working = self.working(1);
+        if value.is_negative() {
+            return -self.atan(&value.abs());
+        }
+        if *value > BigDecimal::one() {
+            // atan(x) = pi/2 - atan(1/x)
+            let inverse = working.atan(&working.div(&BigDecimal::one(), value));
+            return self.round(&working.sub(&working.pi().half(), &inverse));
+        }
+        // atan(x) = 2 * atan(x / (1 + sqrt(1 + x^2))), halving x each time.
+        let mut reduced = value.clone();
+        for _ in 0..3 {
+            let one_plus_squared = working.add(&BigDecimal::one(), &working.mul(&reduced, &reduced));
+            let denominator = working.add(&BigDecimal::one(), &working.sqrt(&one_plus_squared).unwrap_or_default());
+            reduced = working.div(&reduced, &denominator);
+        }
+        self.round(&working.mul(&working.atan_series(&reduced), &BigDecimal::from(8)))
+    }
+
+    #[must_use]
+    pub fn atan2(&self, y: &BigDecimal, x: &BigDecimal) -> BigDecimal {
+        let working = self.working(0);
+        let pi = working.pi();
+        let result = match (x.is_zero(), x.is_positive()) {
+            (true, _) if y.is_zero() => BigDecimal::zero(),
+            (true, _) if y.is_negative() => -pi.half(),
+            (true, _) => pi.half(),
+            (false, true) => working.atan(&working.div(y, x)),
+            (false, false) if y.is_negative() => working.sub(&working.atan(&working.div(y, x)), &pi),
+            (false, false) => working.add(&working.atan(&working.div(y, x)), &pi),
+        };
+        self.round(&result)
+    }
+
+    /// Returns `None` outside of [-1, 1].
+    #[must_use]
+    pub fn asin(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        let working = self.working(0);
+        let cosine = working.sqrt(&working.sub(&BigDecimal::one(), &working.mul(value, value)))?;
+        Some(self.round(&working.atan2(value, &cosine)))
+    }
+
+    // sin or cos of x, reduced to [-pi, pi] first. The larger x is, the more digits of pi the reduction needs.
+    fn sin_or_cos(&self, value: &BigDecimal, cosine: bool) -> Option<BigDecimal> {
+        let magnitude = value.order_of_magnitude();
+        if magnitude > MAX_TRIGONOMETRIC_MAGNITUDE {
+            return None;
+        }
+        let extra = u64::try_from(magnitude.max(0)).unwrap_or(0);
+        let working = self.working(extra);
+        let tau = working.pi().double();
+        let turns = working.div(value, &tau).with_scale_round(0, RoundingMode::HalfEven);
+        let reduced = working.sub(value, &working.mul(&turns, &tau));
+        let reduced_squared = working.mul(&reduced, &reduced);
+        let mut term = if cosine { BigDecimal::one() } else { reduced };
+        let mut sum = term.clone();
+        for n in (u32::from(!cosine) + 1..).step_by(2) {
+            term = -working.div(&working.mul(&term, &reduced_squared), &BigDecimal::from(n * (n + 1)));
+            if working.negligible(&term, &sum) {
+                break;
+            }
+            sum = working.add(&sum, &term);
+        }
+        Some(self.round(&sum))
+    }
+
+    /// Returns `None` for numbers too large to be reduced to [-pi, pi].
+    #[must_use]
+    pub fn sin(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        self.sin_or_cos(value, false)
+    }
+
+    /// Returns `None` for numbers too large to be reduced to [-pi, pi].
+    #[must_use]
+    pub fn cos(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        self.sin_or_cos(value, true)
+    }
+
+    /// Returns `None` where the cosine is zero, or for numbers too large to be reduced to [-pi, pi].
+    #[must_use]
+    pub fn tan(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        let working = self.working(0);
+        let cosine = working.cos(value).filter(|cosine| !cosine.is_zero())?;
+        Some(self.round(&working.div(&working.sin(value)?, &cosine)))
+    }
+}
+
+fn power_of_ten(exponent: u64) -> BigInt {
+    Pow::pow(BigInt::from(10), exponent)
+}
+
+fn digit_count(value: &BigInt) -> i64 {
+    i64::try_from(value.magnitude().to_str_radix(10).len()).unwrap_or(i64::MAX)
+}
+
+#[must_use]
+pub fn to_rational(value: &BigDecimal) -> BigRational {
+    let (digits, scale) = value.as_bigint_and_exponent();
+    if scale >= 0 {
+        BigRational::new(digits, power_of_ten(scale.unsigned_abs()))
+    } else {
+        BigRational::from_integer(digits * power_of_ten(scale.unsigned_abs()))
+    }
+}
+
+#[must_use]
+pub fn to_f64(value: &BigDecimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
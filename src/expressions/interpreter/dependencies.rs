@@ -0,0 +1,180 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use crate::expressions::parser::ast::Expression;
+use crate::expressions::{builtins, prelude};
+
+/// The names each variable and function of a session is defined in terms of, kept alongside their definitions so
+/// that cycles are caught before they are created. A name can depend on names that aren't defined (yet), which are
+/// then simply leaves of the graph.
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    dependencies: HashMap<String, BTreeSet<String>>,
+}
+
+impl DependencyGraph {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the names `name` directly depends on, replacing its previous definition.
+    pub fn insert(&mut self, name: &str, dependencies: BTreeSet<String>) {
+        self.dependencies.insert(name.into(), dependencies);
+    }
+
+    /// Forgets the definition of `name`. The names defined in terms of it still depend on it.
+    pub fn remove(&mut self, name: &str) {
+        self.dependencies.remove(name);
+    }
+
+    /// Forgets every definition.
+    pub fn clear(&mut self) {
+        self.dependencies.clear();
+    }
+
+    /// The names `name` is directly defined in terms of, sorted.
+    #[must_use]
+    pub fn dependencies(&self, name: &str) -> Vec<&str> {
+        self.dependencies.get(name).map_or_else(Vec::new, |names| names.iter().map(String::as_str).collect())
+    }
+
+    /// The names directly defined in terms of `name`, sorted.
+    #[must_use]
+    pub fn dependents(&self, name: &str) -> Vec<&str> {
+        let mut dependents: Vec<&str> = self
+            .dependencies
+            .iter()
+            .filter(|(_, dependencies)| dependencies.contains(name))
+            .map(|(dependent, _)| dependent.as_str())
+            .collect();
+        dependents.sort_unstable();
+        dependents
+    }
+
+    /// The names `name` is defined in terms of, directly or through other names, sorted.
+    #[must_use]
+    pub fn all_dependencies(&self, name: &str) -> BTreeSet<&str> {
+        Self::reachable(name, |name| self.dependencies(name))
+    }
+
+    /// The names defined in terms of `name`, directly or through other names, sorted.
+    #[must_use]
+    pub fn all_dependents(&self, name: &str) -> BTreeSet<&str> {
+        Self::reachable(name, |name| self.dependents(name))
+    }
+
+    // The names reachable from `name` following `next`, `name` itself only if it is on a cycle.
+    fn reachable<'a>(name: &str, next: impl Fn(&str) -> Vec<&'a str>) -> BTreeSet<&'a str> {
+        let mut reached = BTreeSet::new();
+        let mut pending = next(name);
+        while let Some(name) = pending.pop() {
+            if reached.insert(name) {
+                pending.extend(next(name));
+            }
+        }
+        reached
+    }
+
+    /// Every name of the graph, defined or only depended on, sorted.
+    #[must_use]
+    pub fn names(&self) -> BTreeSet<&str> {
+        self.dependencies
+            .iter()
+            .flat_map(|(name, dependencies)| std::iter::once(name).chain(dependencies))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// The cycle that defining `name` in terms of `dependencies` would create, if any, from `name` back to itself:
+    /// `[a, b, c, a]` for `a = b` when `b = c` and `c = a`.
+    #[must_use]
+    pub fn cycle(&self, name: &str, dependencies: &BTreeSet<String>) -> Option<Vec<String>> {
+        self.cycle_through(name, dependencies, |_| true)
+    }
+
+    /// The cycle that defining `name` in terms of `dependencies` would create going through at least one name
+    /// `through` holds for, if any, from `name` back to itself. The other cycles, such as the ones of recursive
+    /// functions, are allowed.
+    #[must_use]
+    pub fn cycle_through(
+        &self,
+        name: &str,
+        dependencies: &BTreeSet<String>,
+        through: impl Fn(&str) -> bool,
+    ) -> Option<Vec<String>> {
+        let mut visited = HashSet::new();
+        dependencies.iter().find_map(|dependency| {
+            let path = self.path(dependency, name, through(name), &through, &mut visited)?;
+            Some(std::iter::once(name.to_string()).chain(path).collect())
+        })
+    }
+
+    // The names leading from `from` to `to`, both included, going through a name `through` holds for unless one
+    // already was (`passed`). `visited` keeps the existing cycles, such as the ones of recursive functions, from
+    // being walked forever.
+    fn path(
+        &self,
+        from: &str,
+        to: &str,
+        passed: bool,
+        through: &impl Fn(&str) -> bool,
+        visited: &mut HashSet<(String, bool)>,
+    ) -> Option<Vec<String>> {
+        let passed = passed || through(from);
+        if from == to {
+            return passed.then(|| vec![from.into()]);
+        }
+        if !visited.insert((from.into(), passed)) {
+            return None;
+        }
+        self.dependencies.get(from)?.iter().find_map(|dependency| {
+            let path = self.path(dependency, to, passed, through, visited)?;
+            Some(std::iter::once(from.to_string()).chain(path).collect())
+        })
+    }
+}
+
+/// The variables and functions the expression refers to, builtin functions and constants left out. `parameters` are
+/// the local names of the function body the expression is, they never refer to a variable.
+#[must_use]
+pub fn references(expression: &Expression, parameters: &[String]) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    collect_references(expression, parameters, &mut names);
+    names
+}
+
+fn collect_references(expression: &Expression, parameters: &[String], names: &mut BTreeSet<String>) {
+    match expression {
+        Expression::Program(_)
+        | Expression::Assignment(_, _)
+        | Expression::Binding(_, _)
+        | Expression::FunctionDefinition(_, _, _)
+        | Expression::Literal(_)
+        | Expression::Eof => {}
+        Expression::Variable(name, _) => {
+            if !parameters.contains(name) && prelude::find(name).is_none() {
+                names.insert(name.clone());
+            }
+        }
+        Expression::Addition(left, right)
+        | Expression::Subtraction(left, right)
+        | Expression::Multiplication(left, right)
+        | Expression::Division(left, right, _)
+        | Expression::Power(left, right, _) => {
+            collect_references(left, parameters, names);
+            collect_references(right, parameters, names);
+        }
+        Expression::UnaryPlus(expr)
+        | Expression::UnaryMinus(expr)
+        | Expression::ParenthesisExpression(expr)
+        | Expression::Quantity(expr, _)
+        | Expression::Conversion(expr, _) => collect_references(expr, parameters, names),
+        Expression::Call(name, arguments, _) => {
+            if builtins::find(name).is_none() {
+                names.insert(name.clone());
+            }
+            for argument in arguments {
+                collect_references(argument, parameters, names);
+            }
+        }
+    }
+}
@@ -0,0 +1,516 @@
+pub mod decimal;
+pub mod dependencies;
+pub mod number;
+
+use crate::expressions::parser::ast::Expression;
+use crate::expressions::parser::ast::Expression::{Assignment, Literal};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+use crate::errors::diagnostic::Diagnostic;
+use crate::errors::interpreter_error::{InterpreterError, InterpreterErrorKind};
+use crate::expressions::interpreter::dependencies::DependencyGraph;
+use crate::expressions::interpreter::number::{Backend, Number};
+use crate::expressions::{builtins, prelude};
+
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 100;
+
+#[derive(Clone)]
+struct Function {
+    parameters: Vec<String>,
+    body: Expression,
+}
+
+/// What a variable holds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Binding {
+    /// `a = b + 1`: an expression, evaluated each time the variable is read, so that it follows `b`.
+    Lazy(Expression),
+    /// `a := b + 1`: the value the expression had when the variable was bound, which doesn't follow `b`.
+    Frozen(Number),
+}
+
+/// Identifies a subscription made with [`Interpreter::subscribe`], to cancel it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(usize);
+
+type Callback = Box<dyn FnMut(&str, &Result<Number, InterpreterError>) + Send>;
+
+struct Subscription {
+    id: SubscriptionId,
+    name: String,
+    callback: Callback,
+    // The value the callback was last told about, none while the variable can't be evaluated
+    value: Option<Number>,
+}
+
+// The subscriptions of an interpreter. The callbacks belong to the interpreter they were registered on, so its clones
+// start without any.
+#[derive(Default)]
+struct Subscriptions {
+    list: Vec<Subscription>,
+    next_id: usize,
+}
+
+impl Clone for Subscriptions {
+    fn clone(&self) -> Self {
+        Self { list: Vec::new(), next_id: self.next_id }
+    }
+}
+
+#[derive(Clone)]
+pub struct Interpreter {
+    mem: HashMap<String, Binding>,
+    functions: HashMap<String, Function>,
+    // What each variable and function is defined in terms of, kept in sync with `mem` and `functions`
+    dependencies: DependencyGraph,
+    // Values of the lazy variables already evaluated, dropped as soon as what they are defined in terms of changes
+    cache: HashMap<String, Number>,
+    // Callbacks of the host told when the value of a variable changes
+    subscriptions: Subscriptions,
+    // Arguments of the user functions being called, the innermost call being last
+    frames: Vec<HashMap<String, Number>>,
+    call_depth: usize,
+    max_call_depth: usize,
+    backend: Backend,
+}
+
+impl Interpreter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_backend(Backend::default())
+    }
+
+    #[must_use]
+    pub fn with_backend(backend: Backend) -> Self {
+        Self {
+            mem: HashMap::new(),
+            functions: HashMap::new(),
+            dependencies: DependencyGraph::new(),
+            cache: HashMap::new(),
+            subscriptions: Subscriptions::default(),
+            frames: Vec::new(),
+            call_depth: 0,
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            backend,
+        }
+    }
+
+    #[must_use]
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    /// Sets how many user function calls can be nested before the evaluation is aborted,
+    /// which is what stops infinitely recursive functions.
+    pub fn set_max_call_depth(&mut self, max_call_depth: usize) {
+        // Values computed with a deeper limit may not be computable anymore
+        self.cache.clear();
+        self.max_call_depth = max_call_depth;
+    }
+
+    /// The variables of the session with what they hold, sorted by name.
+    #[must_use]
+    pub fn variables(&self) -> Vec<(&str, &Binding)> {
+        let mut variables: Vec<(&str, &Binding)> =
+            self.mem.iter().map(|(name, binding)| (name.as_str(), binding)).collect();
+        variables.sort_by_key(|(name, _)| *name);
+        variables
+    }
+
+    /// The functions defined in the session with their parameters and body, sorted by name.
+    #[must_use]
+    pub fn functions(&self) -> Vec<(&str, &[String], &Expression)> {
+        let mut functions: Vec<(&str, &[String], &Expression)> = self
+            .functions
+            .iter()
+            .map(|(name, function)| (name.as_str(), function.parameters.as_slice(), &function.body))
+            .collect();
+        functions.sort_by_key(|(name, _, _)| *name);
+        functions
+    }
+
+    /// What the variables and functions of the session are defined in terms of.
+    #[must_use]
+    pub fn dependency_graph(&self) -> &DependencyGraph {
+        &self.dependencies
+    }
+
+    /// The variables and functions `name` is defined in terms of, directly or not, sorted.
+    #[must_use]
+    pub fn dependencies_of(&self, name: &str) -> Vec<&str> {
+        self.dependencies.all_dependencies(name).into_iter().collect()
+    }
+
+    /// The variables and functions defined in terms of `name`, directly or not, sorted. They are the ones whose
+    /// value changes when `name` is redefined.
+    #[must_use]
+    pub fn dependents_of(&self, name: &str) -> Vec<&str> {
+        self.dependencies.all_dependents(name).into_iter().collect()
+    }
+
+    /// The dependency graph of the session in the Graphviz DOT format, an edge going from each variable or function
+    /// to the ones it is defined in terms of. Functions are boxes, and names that aren't defined are dashed.
+    #[must_use]
+    pub fn dependency_dot(&self) -> String {
+        let mut dot = String::from("digraph dependencies {\n");
+        for name in self.dependencies.names() {
+            let attributes = match self.functions.get(name) {
+                Some(function) => format!(" [shape=box, label=\"{name}({})\"]", function.parameters.join(", ")),
+                None if self.mem.contains_key(name) => String::new(),
+                None => String::from(" [style=dashed]"),
+            };
+            let _ = writeln!(dot, "    \"{name}\"{attributes};");
+        }
+        for name in self.dependencies.names() {
+            for dependency in self.dependencies.dependencies(name) {
+                let _ = writeln!(dot, "    \"{name}\" -> \"{dependency}\";");
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
+    /// Calls `callback` with the name and the new value of the variable `name` each time it changes: when it is
+    /// defined, or when a variable or function it is defined in terms of, directly or not, is. The value is an error
+    /// when the variable can't be evaluated anymore, e.g. once something it needs is removed. The variable doesn't
+    /// have to be defined yet, and the callback isn't called when a definition leaves its value as it was.
+    ///
+    /// Subscriptions aren't cloned along with the interpreter: a clone starts without any, and only tells its own
+    /// subscribers about its changes. See [`Interpreter::restore`] to roll back to a clone while keeping them.
+    pub fn subscribe(
+        &mut self,
+        name: &str,
+        callback: impl FnMut(&str, &Result<Number, InterpreterError>) + Send + 'static,
+    ) -> SubscriptionId {
+        let id = SubscriptionId(self.subscriptions.next_id);
+        self.subscriptions.next_id += 1;
+        let value = self.value(name).ok();
+        self.subscriptions.list.push(Subscription { id, name: name.into(), callback: Box::new(callback), value });
+        id
+    }
+
+    /// Cancels a subscription, returning whether it was still there.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let count = self.subscriptions.list.len();
+        self.subscriptions.list.retain(|subscription| subscription.id != id);
+        self.subscriptions.list.len() != count
+    }
+
+    /// Puts back the variables and functions of `snapshot`, a clone made earlier, keeping the subscriptions of this
+    /// interpreter. Their callbacks are told about the variables the snapshot gives another value, as if they had
+    /// been redefined.
+    pub fn restore(&mut self, snapshot: Interpreter) {
+        let subscriptions = std::mem::take(&mut self.subscriptions);
+        *self = snapshot;
+        self.subscriptions = subscriptions;
+        let names: BTreeSet<String> =
+            self.subscriptions.list.iter().map(|subscription| subscription.name.clone()).collect();
+        self.notify_all(&names);
+    }
+
+    /// The value of the variable or constant `name`. Its errors have no span, as they aren't about a line.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if there is no such variable, or if its expression can't be evaluated.
+    pub fn value(&mut self, name: &str) -> Result<Number, InterpreterError> {
+        self.lookup(name).map_err(InterpreterError::without_span)
+    }
+
+    /// Forgets the variable or function of that name, returning whether there was one.
+    /// Whatever was defined in terms of it is kept, and fails to evaluate until it is defined again.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.invalidate(name);
+        self.dependencies.remove(name);
+        let removed = self.mem.remove(name).is_some() | self.functions.remove(name).is_some();
+        self.notify(name);
+        removed
+    }
+
+    /// Forgets every variable and function.
+    pub fn clear(&mut self) {
+        self.mem.clear();
+        self.functions.clear();
+        self.dependencies.clear();
+        self.cache.clear();
+        let names: BTreeSet<String> =
+            self.subscriptions.list.iter().map(|subscription| subscription.name.clone()).collect();
+        self.notify_all(&names);
+    }
+
+    // Tells the subscribers of `name` and of everything defined in terms of it, directly or not, about their new
+    // values, once `name` has been redefined.
+    fn notify(&mut self, name: &str) {
+        if self.subscriptions.list.is_empty() {
+            return;
+        }
+        let mut names: BTreeSet<String> = self.dependencies.all_dependents(name).into_iter().map(String::from).collect();
+        names.insert(name.into());
+        self.notify_all(&names);
+    }
+
+    // Tells the subscribers of `names` whose value changed about it, in the order of the names.
+    fn notify_all(&mut self, names: &BTreeSet<String>) {
+        for name in names {
+            if !self.subscriptions.list.iter().any(|subscription| &subscription.name == name) {
+                continue;
+            }
+            let result = self.value(name);
+            for subscription in self.subscriptions.list.iter_mut().filter(|subscription| &subscription.name == name) {
+                if result.as_ref().ok() != subscription.value.as_ref() {
+                    subscription.value = result.as_ref().ok().cloned();
+                    (subscription.callback)(name, &result);
+                }
+            }
+        }
+    }
+
+    // Drops the cached values of `name` and of everything defined in terms of it, directly or not, as they may
+    // change along with it.
+    fn invalidate(&mut self, name: &str) {
+        self.cache.remove(name);
+        for dependent in self.dependencies.all_dependents(name) {
+            self.cache.remove(dependent);
+        }
+    }
+
+    // Builtin functions and prelude constants can't be redefined, be it as a variable, a function or a parameter.
+    fn ensure_definable(name: &str) -> Result<(), InterpreterError> {
+        if builtins::find(name).is_some() {
+            Err(InterpreterError::new(InterpreterErrorKind::RedefinedBuiltin { name: name.into() })
+                .with_help("pick another name"))
+        } else if prelude::find(name).is_some() {
+            Err(InterpreterError::new(InterpreterErrorKind::RedefinedConstant { name: name.into() })
+                .with_help("pick another name"))
+        } else {
+            Ok(())
+        }
+    }
+
+    // Only quantities of the same dimension can be added or subtracted: `3 m + 2 s` makes no sense.
+    fn ensure_same_dimension(operation: &str, left: &Number, right: &Number) -> Result<(), InterpreterError> {
+        if left.dimension() == right.dimension() {
+            Ok(())
+        } else {
+            Err(InterpreterErrorKind::DimensionMismatch {
+                operation: operation.into(),
+                left: left.to_string(),
+                right: right.to_string(),
+                expected: left.dimension(),
+                found: right.dimension(),
+            }
+            .into())
+        }
+    }
+
+    // The value of a constant, of an argument of the function being called, or of a variable. The errors of the
+    // expression of a variable are about the line it was defined on, whose span the reader replaces with its own.
+    fn lookup(&mut self, identifier: &str) -> Result<Number, InterpreterError> {
+        if let Some(constant) = prelude::find(identifier) {
+            Ok(constant.evaluate(self.backend))
+        } else if let Some(value) = self.frames.last().and_then(|frame| frame.get(identifier)) {
+            Ok(value.clone())
+        } else if let Some(value) = self.cache.get(identifier) {
+            Ok(value.clone())
+        } else if let Some(binding) = self.mem.get(identifier) {
+            let expr = match binding {
+                Binding::Lazy(expr) => expr.clone(),
+                Binding::Frozen(value) => return Ok(value.clone()),
+            };
+            let value = if self.frames.is_empty() {
+                self.interpret(expr)?
+            } else {
+                // Variables don't see the arguments of the function that reads them
+                self.interpret_in_frame(HashMap::new(), expr)?
+            };
+            self.cache.insert(identifier.into(), value.clone());
+            Ok(value)
+        } else {
+            Err(InterpreterError::new(InterpreterErrorKind::UndefinedVariable { name: identifier.into() })
+                .with_help(format!("define it first, e.g. {identifier} = 1")))
+        }
+    }
+
+    // Evaluates an expression with its own set of local names, e.g. the body of a function with its arguments.
+    fn interpret_in_frame(
+        &mut self,
+        frame: HashMap<String, Number>,
+        expression: Expression,
+    ) -> Result<Number, InterpreterError> {
+        self.frames.push(frame);
+        let result = self.interpret(expression);
+        self.frames.pop();
+        result
+    }
+
+    // Defines a function in place of the variable or function of that name. Functions can call each other, themselves
+    // included, as their calls are limited, but one can't be defined in terms of a variable defined in terms of it.
+    fn define_function(
+        &mut self,
+        name: &str,
+        parameters: Vec<String>,
+        body: Expression,
+    ) -> Result<Number, InterpreterError> {
+        for defined_name in std::iter::once(name).chain(parameters.iter().map(String::as_str)) {
+            Self::ensure_definable(defined_name)?;
+        }
+        let references = dependencies::references(&body, &parameters);
+        let is_variable = |other: &str| other != name && self.mem.contains_key(other);
+        if let Some(cycle) = self.dependencies.cycle_through(name, &references, is_variable) {
+            return Err(InterpreterError::new(InterpreterErrorKind::CyclicDefinition { cycle })
+                .with_help(format!("{name} can't be defined in terms of itself")));
+        }
+        self.mem.remove(name);
+        self.invalidate(name);
+        self.dependencies.insert(name, references);
+        self.functions.insert(name.into(), Function { parameters, body });
+        self.notify(name);
+        Ok(Number::Float(f64::NAN))
+    }
+
+    fn call_function(&mut self, name: &str, arguments: Vec<Number>) -> Result<Number, InterpreterError> {
+        if let Some(builtin) = builtins::find(name) {
+            return builtin.call(&arguments);
+        }
+        let function = self
+            .functions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| InterpreterError::new(InterpreterErrorKind::UndefinedFunction { name: name.into() }))?;
+        if function.parameters.len() != arguments.len() {
+            return Err(InterpreterErrorKind::ArityMismatch {
+                function: format!("{name}({})", function.parameters.join(", ")),
+                expected: format!("{} argument(s)", function.parameters.len()),
+                found: arguments.len(),
+            }
+            .into());
+        }
+        if self.call_depth >= self.max_call_depth {
+            return Err(InterpreterErrorKind::CallDepthExceeded {
+                function: name.into(),
+                max_depth: self.max_call_depth,
+            }
+            .into());
+        }
+        let frame = function.parameters.into_iter().zip(arguments).collect();
+        self.call_depth += 1;
+        let result = self.interpret_in_frame(frame, function.body);
+        self.call_depth -= 1;
+        result
+    }
+
+    /// Function definitions have no value, so interpreting one returns NaN.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if it fails interpreting a line, see [`InterpreterErrorKind`] for the possible reasons.
+    pub fn interpret(&mut self, ast: Expression) -> Result<Number, InterpreterError> {
+        match ast {
+            Expression::Program(statements) => {
+                let mut result = Err(InterpreterErrorKind::NothingToEvaluate.into());
+                for statement in statements {
+                    let span = statement.span;
+                    result = Ok(self
+                        .interpret(statement.expression)
+                        .map_err(|err| if err.span().is_some() { err } else { err.with_span(span) })?);
+                }
+                result
+            }
+            Assignment(identifier, expr) => {
+                Self::ensure_definable(&identifier)?;
+                let references = dependencies::references(&expr, &[]);
+                if let Some(cycle) = self.dependencies.cycle(&identifier, &references) {
+                    return Err(InterpreterError::new(InterpreterErrorKind::CyclicDefinition { cycle })
+                        .with_help(format!("{identifier} can't be defined in terms of itself")));
+                }
+                self.invalidate(&identifier);
+                self.dependencies.insert(&identifier, references);
+                self.functions.remove(&identifier);
+                self.mem.insert(identifier.clone(), Binding::Lazy(*expr.clone()));
+                let result = self.interpret(*expr);
+                if let Ok(value) = &result {
+                    self.cache.insert(identifier.clone(), value.clone());
+                }
+                // The variable is defined even when its value can't be computed, which its subscribers are told
+                self.notify(&identifier);
+                result
+            }
+            Expression::Binding(identifier, expr) => {
+                Self::ensure_definable(&identifier)?;
+                // The expression can read the previous value of the variable, as in `n := n + 1`
+                let value = self.interpret(*expr)?;
+                self.invalidate(&identifier);
+                self.dependencies.insert(&identifier, BTreeSet::new());
+                self.functions.remove(&identifier);
+                self.mem.insert(identifier.clone(), Binding::Frozen(value.clone()));
+                self.notify(&identifier);
+                Ok(value)
+            }
+            Expression::Addition(left, right) => {
+                let left = self.interpret(*left)?;
+                let right = self.interpret(*right)?;
+                Self::ensure_same_dimension("add", &left, &right)?;
+                Ok(left + right)
+            }
+            Expression::Subtraction(left, right) => {
+                let left = self.interpret(*left)?;
+                let right = self.interpret(*right)?;
+                Self::ensure_same_dimension("subtract", &left, &right)?;
+                Ok(left - right)
+            }
+            Expression::UnaryPlus(expr) => {
+                Ok(self.interpret(*expr)?) // Let's pretend it is somehow useful
+            }
+            Expression::UnaryMinus(expr) => Ok(-self.interpret(*expr)?),
+            Expression::ParenthesisExpression(expr) => Ok(self.interpret(*expr)?),
+            Expression::Multiplication(left, right) => {
+                Ok(self.interpret(*left)? * self.interpret(*right)?)
+            }
+            Expression::Division(left, right, span) => {
+                let right_operand = self.interpret(*right)?;
+                if right_operand.is_zero() {
+                    Err(InterpreterError::new(InterpreterErrorKind::DivisionByZero).with_span(span))
+                } else {
+                    Ok(self.interpret(*left)? / right_operand)
+                }
+            }
+            Expression::Power(base, exponent, span) => {
+                let base = self.interpret(*base)?;
+                let exponent = self.interpret(*exponent)?;
+                base.pow(&exponent).map_err(|err| err.with_span(span))
+            }
+            Expression::FunctionDefinition(name, parameters, body) => self.define_function(&name, parameters, *body),
+            Expression::Call(name, arguments, span) => {
+                let arguments = arguments
+                    .into_iter()
+                    .map(|argument| self.interpret(argument))
+                    .collect::<Result<Vec<Number>, InterpreterError>>()?;
+                // What fails within the body of the function is reported at the call
+                self.call_function(&name, arguments).map_err(|err| err.with_span(span))
+            }
+            Expression::Quantity(expr, unit) => Ok(self.interpret(*expr)?.with_unit(&unit)),
+            Expression::Conversion(expr, unit) => {
+                let value = self.interpret(*expr)?;
+                value.convert(&unit).ok_or_else(|| {
+                    InterpreterError::new(InterpreterErrorKind::InvalidConversion {
+                        value: value.to_string(),
+                        unit: unit.to_string(),
+                        from: value.dimension(),
+                        to: unit.dimension(),
+                    })
+                })
+            }
+            Literal(numeral) => Ok(Number::from_numeral(&numeral, self.backend)),
+            Expression::Variable(identifier, span) => self.lookup(&identifier).map_err(|err| err.with_span(span)),
+            Expression::Eof => {
+                Err(InterpreterErrorKind::NothingToEvaluate.into())
+            }
+        }
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,708 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use bigdecimal::{BigDecimal, RoundingMode};
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{One, Pow, Signed, ToPrimitive, Zero};
+use crate::errors::interpreter_error::{InterpreterError, InterpreterErrorKind};
+use crate::expressions::interpreter::decimal::{self, DecimalContext};
+use crate::expressions::parser::ast::Numeral;
+use crate::expressions::units::{Dimension, Unit};
+
+// Above this, exact powers get too big to be worth computing and fall back to floats.
+const MAX_EXACT_EXPONENT: i32 = 10_000;
+// Number of digits shown when a fraction has no finite decimal expansion, e.g. 1/3.
+const REPEATING_DECIMAL_DIGITS: usize = 20;
+
+/// How the interpreter represents numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Double precision floating point numbers: fast, but `0.1 + 0.2` isn't exactly `0.3`.
+    #[default]
+    Float,
+    /// Fractions of arbitrarily large integers: `1/3 * 3` is exactly `1`. Results that can't be represented
+    /// exactly, such as `sqrt(2)` or anything involving `pi`, fall back to floats.
+    Rational,
+    /// Decimal numbers with a chosen number of significant digits and rounding mode: `1/3` is `0.333...3` up to
+    /// the precision, and so are functions such as `sqrt`, `ln` or `sin`.
+    Decimal(DecimalContext),
+}
+
+/// A value computed by the interpreter.
+///
+/// Displaying a rational prints it as a fraction (`1/3`). The alternate flag (`{:#}`) prints it as a decimal
+/// instead, which is exact when the fraction has a finite decimal expansion and rounded otherwise (`0.333...`).
+/// A precision (`{:.3}`) rounds any number to that many decimals, except decimals so large or so small that
+/// they are printed in scientific notation. Complex numbers are printed in rectangular form (`3+4i`), see
+/// [`Number::display`] for the polar form. Quantities are followed by their unit (`3.2 km`).
+#[derive(Debug, Clone)]
+pub enum Number {
+    Float(f64),
+    Rational(BigRational),
+    Decimal(BigDecimal, DecimalContext),
+    /// Complex numbers always use floats, whatever the backend is. Their imaginary part is never zero, see
+    /// [`Number::complex`].
+    Complex(Complex64),
+    /// A value in a unit, e.g. `3 km` is `Quantity(3, km)`. The unit is never dimensionless, see
+    /// [`Number::with_unit`].
+    Quantity(Box<Number>, Unit),
+}
+
+/// How results are displayed, see [`Number::display`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Notation {
+    /// Displays exact numbers as decimals rather than fractions.
+    pub decimal: bool,
+    /// Displays complex numbers in polar form, `r∠θ` with θ in radians, rather than in rectangular form.
+    pub polar: bool,
+}
+
+impl Number {
+    /// A complex number, or a float if its imaginary part is zero, so that `i * i` is just `-1`.
+    #[must_use]
+    pub fn complex(value: Complex64) -> Self {
+        if value.im == 0.0 {
+            Number::Float(value.re)
+        } else {
+            Number::Complex(value)
+        }
+    }
+
+    #[must_use]
+    pub fn from_numeral(numeral: &Numeral, backend: Backend) -> Self {
+        if numeral.is_imaginary() {
+            return Number::complex(Complex64::new(0.0, numeral.to_f64()));
+        }
+        match backend {
+            Backend::Float => Number::Float(numeral.to_f64()),
+            Backend::Rational => Number::Rational(numeral.value().clone()),
+            Backend::Decimal(context) => Number::Decimal(context.from_rational(numeral.value()), context),
+        }
+    }
+
+    /// Multiplies the number by one of the unit, merging it with the unit the number may already have. Units that
+    /// cancel out give a plain number: `2 km/m` is 2000.
+    #[must_use]
+    pub fn with_unit(self, unit: &Unit) -> Number {
+        let (value, own_unit) = self.into_parts();
+        Number::from_parts(value, &own_unit.multiply(unit))
+    }
+
+    fn into_parts(self) -> (Number, Unit) {
+        match self {
+            Number::Quantity(value, unit) => (*value, unit),
+            number => (number, Unit::default()),
+        }
+    }
+
+    fn from_parts(value: Number, unit: &Unit) -> Number {
+        if unit.is_empty() {
+            value
+        } else if unit.dimension().is_dimensionless() {
+            value * Number::Rational(unit.scale())
+        } else {
+            Number::Quantity(Box::new(value), unit.clone())
+        }
+    }
+
+    #[must_use]
+    pub fn unit(&self) -> Option<&Unit> {
+        match self {
+            Number::Quantity(_, unit) => Some(unit),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn dimension(&self) -> Dimension {
+        self.unit().map(Unit::dimension).unwrap_or_default()
+    }
+
+    /// The same quantity, expressed in another unit. Returns `None` if the dimensions don't match.
+    #[must_use]
+    pub fn convert(&self, unit: &Unit) -> Option<Number> {
+        Some(Number::from_parts(self.value_in(unit)?, unit))
+    }
+
+    // The value of the number, once converted to a unit of the same dimension. The value is multiplied by the
+    // numerator of the ratio of the units and divided by its denominator, rather than multiplied by the ratio, so
+    // that a float is only rounded once: 5 µm is 5 / 1000000 m, where 5 * 0.000001 would be 0.0000049999999999999996.
+    fn value_in(&self, unit: &Unit) -> Option<Number> {
+        let (value, own_unit) = self.clone().into_parts();
+        if own_unit.dimension() != unit.dimension() {
+            return None;
+        }
+        let (numerator, denominator): (BigInt, BigInt) = (own_unit.scale() / unit.scale()).into();
+        let value = if numerator.is_one() { value } else { value * Number::Rational(numerator.into()) };
+        Some(if denominator.is_one() { value } else { value / Number::Rational(denominator.into()) })
+    }
+
+    // The values of two numbers of the same dimension, both in the unit of the first one.
+    fn values_in_same_unit(&self, other: &Number) -> Option<(Number, Number)> {
+        let (value, unit) = self.clone().into_parts();
+        Some((value, other.value_in(&unit)?))
+    }
+
+    // Adds or subtracts two numbers of the same dimension, in the unit of the left one. The dimensions are
+    // expected to have been checked already, mismatching ones give NaN.
+    fn add_quantities(&self, rhs: &Number, operation: fn(Number, Number) -> Number) -> Number {
+        let (value, unit) = self.clone().into_parts();
+        match rhs.value_in(&unit) {
+            Some(right) => Number::from_parts(operation(value, right), &unit),
+            None => Number::Float(f64::NAN),
+        }
+    }
+
+    fn is_quantity(&self) -> bool {
+        matches!(self, Number::Quantity(..))
+    }
+
+    /// Complex numbers aren't real numbers and quantities aren't plain numbers, so they give NaN.
+    #[must_use]
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Float(value) => *value,
+            Number::Rational(value) => value.to_f64().unwrap_or(f64::NAN),
+            Number::Decimal(value, _) => decimal::to_f64(value),
+            Number::Complex(_) | Number::Quantity(..) => f64::NAN,
+        }
+    }
+
+    #[must_use]
+    pub fn to_complex(&self) -> Complex64 {
+        match self {
+            Number::Complex(value) => *value,
+            real => Complex64::new(real.to_f64(), 0.0),
+        }
+    }
+
+    #[must_use]
+    pub fn display(&self, notation: Notation) -> String {
+        match self {
+            Number::Quantity(value, unit) => format!("{} {unit}", value.display(notation)),
+            Number::Complex(value) if notation.polar => format!("{}∠{}", value.norm(), value.arg()),
+            _ if notation.decimal => format!("{self:#}"),
+            _ => self.to_string(),
+        }
+    }
+
+    /// The context of a decimal number.
+    #[must_use]
+    pub fn decimal_context(&self) -> Option<DecimalContext> {
+        match self {
+            Number::Decimal(_, context) => Some(*context),
+            _ => None,
+        }
+    }
+
+    /// Floats can't be converted without giving the illusion of more precision than they have.
+    #[must_use]
+    pub fn to_decimal(&self, context: &DecimalContext) -> Option<BigDecimal> {
+        match self {
+            Number::Float(_) | Number::Complex(_) | Number::Quantity(..) => None,
+            Number::Rational(value) => Some(context.from_rational(value)),
+            Number::Decimal(value, _) => Some(value.clone()),
+        }
+    }
+
+    fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Number::Float(_) | Number::Complex(_) | Number::Quantity(..) => None,
+            Number::Rational(value) => Some(value.clone()),
+            Number::Decimal(value, _) => Some(decimal::to_rational(value)),
+        }
+    }
+
+    fn to_exact_integer(&self) -> Option<i32> {
+        self.to_rational()
+            .filter(BigRational::is_integer)
+            .and_then(|value| value.to_integer().to_i32())
+    }
+
+    // Unlike `to_exact_integer`, also accepts floats with an integer value.
+    fn to_integer(&self) -> Option<i32> {
+        match self {
+            #[allow(clippy::cast_possible_truncation)]
+            Number::Float(value) if value.fract() == 0.0 && value.abs() <= f64::from(i32::MAX) => Some(*value as i32),
+            exact => exact.to_exact_integer(),
+        }
+    }
+
+    /// The name of the representation of the number: `float`, `rational`, `decimal`, `complex` or `quantity`.
+    #[must_use]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Number::Float(_) => "float",
+            Number::Rational(_) => "rational",
+            Number::Decimal(_, _) => "decimal",
+            Number::Complex(_) => "complex",
+            Number::Quantity(_, _) => "quantity",
+        }
+    }
+
+    #[must_use]
+    pub fn is_exact(&self) -> bool {
+        match self {
+            Number::Quantity(value, _) => value.is_exact(),
+            number => matches!(number, Number::Rational(_)),
+        }
+    }
+
+    #[must_use]
+    pub fn is_complex(&self) -> bool {
+        matches!(self, Number::Complex(_))
+    }
+
+    #[must_use]
+    pub fn is_nan(&self) -> bool {
+        match self {
+            Number::Float(value) => value.is_nan(),
+            Number::Complex(value) => value.is_nan(),
+            Number::Quantity(value, _) => value.is_nan(),
+            _ => false,
+        }
+    }
+
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Float(value) => *value == 0.0,
+            Number::Rational(value) => value.is_zero(),
+            Number::Decimal(value, _) => value.is_zero(),
+            Number::Complex(value) => value.is_zero(),
+            Number::Quantity(value, _) => value.is_zero(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Number::Float(value) => *value < 0.0,
+            Number::Rational(value) => value.is_negative(),
+            Number::Decimal(value, _) => value.is_negative(),
+            Number::Complex(_) => false,
+            Number::Quantity(value, _) => value.is_negative(),
+        }
+    }
+
+    // Applies the operation matching the representation of the operands: mixing a complex with anything gives a
+    // complex, mixing a float with a real number gives a float, and mixing a decimal with a rational gives a decimal.
+    fn combine(
+        self,
+        rhs: Number,
+        float: fn(f64, f64) -> f64,
+        rational: fn(BigRational, BigRational) -> BigRational,
+        decimal: fn(&DecimalContext, &BigDecimal, &BigDecimal) -> BigDecimal,
+        complex: fn(Complex64, Complex64) -> Complex64,
+    ) -> Number {
+        match (self, rhs) {
+            (Number::Rational(left), Number::Rational(right)) => Number::Rational(rational(left, right)),
+            (left, right) if left.is_complex() || right.is_complex() => {
+                Number::complex(complex(left.to_complex(), right.to_complex()))
+            }
+            (left, right) => {
+                if let Some(context) = left.decimal_context().or_else(|| right.decimal_context()) {
+                    if let (Some(left), Some(right)) = (left.to_decimal(&context), right.to_decimal(&context)) {
+                        return Number::Decimal(decimal(&context, &left, &right), context);
+                    }
+                }
+                Number::Float(float(left.to_f64(), right.to_f64()))
+            }
+        }
+    }
+
+    fn map(
+        &self,
+        float: fn(f64) -> f64,
+        rational: fn(&BigRational) -> BigRational,
+        decimal: fn(&BigDecimal) -> BigDecimal,
+    ) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(float(*value)),
+            Number::Rational(value) => Number::Rational(rational(value)),
+            Number::Decimal(value, context) => Number::Decimal(decimal(value).normalized(), *context),
+            Number::Complex(_) => Number::Float(f64::NAN),
+            Number::Quantity(value, unit) => {
+                Number::Quantity(Box::new(value.map(float, rational, decimal)), unit.clone())
+            }
+        }
+    }
+
+    // Applies an operation to the value of a quantity, keeping its unit.
+    fn in_same_unit(value: &Number, unit: &Unit, operation: fn(&Number) -> Number) -> Number {
+        Number::Quantity(Box::new(operation(value)), unit.clone())
+    }
+
+    /// The modulus of complex numbers.
+    #[must_use]
+    pub fn abs(&self) -> Number {
+        match self {
+            Number::Complex(value) => Number::Float(value.norm()),
+            Number::Quantity(value, unit) => Number::in_same_unit(value, unit, Number::abs),
+            real => real.map(f64::abs, BigRational::abs, BigDecimal::abs),
+        }
+    }
+
+    #[must_use]
+    pub fn real_part(&self) -> Number {
+        match self {
+            Number::Complex(value) => Number::Float(value.re),
+            Number::Quantity(value, unit) => Number::in_same_unit(value, unit, Number::real_part),
+            real => real.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn imaginary_part(&self) -> Number {
+        match self {
+            Number::Complex(value) => Number::Float(value.im),
+            Number::Quantity(value, unit) => Number::in_same_unit(value, unit, Number::imaginary_part),
+            real => real.zero(),
+        }
+    }
+
+    // Zero, with the same representation as this number.
+    fn zero(&self) -> Number {
+        match self {
+            Number::Float(_) | Number::Complex(_) => Number::Float(0.0),
+            Number::Rational(_) => Number::Rational(BigRational::zero()),
+            Number::Decimal(_, context) => Number::Decimal(BigDecimal::zero(), *context),
+            Number::Quantity(value, _) => value.zero(),
+        }
+    }
+
+    #[must_use]
+    pub fn conjugate(&self) -> Number {
+        match self {
+            Number::Complex(value) => Number::Complex(value.conj()),
+            Number::Quantity(value, unit) => Number::in_same_unit(value, unit, Number::conjugate),
+            real => real.clone(),
+        }
+    }
+
+    /// The angle of complex numbers in radians, between -pi and pi: 0 for positive numbers and pi for negative ones.
+    #[must_use]
+    pub fn argument(&self) -> Number {
+        match self {
+            Number::Complex(value) => Number::Float(value.arg()),
+            Number::Quantity(value, _) => value.argument(),
+            Number::Decimal(value, context) if value.is_negative() => Number::Decimal(context.pi(), *context),
+            real if real.is_negative() => Number::Float(std::f64::consts::PI),
+            real => real.zero(),
+        }
+    }
+
+    #[must_use]
+    pub fn floor(&self) -> Number {
+        self.map(f64::floor, BigRational::floor, |value| value.with_scale_round(0, RoundingMode::Floor))
+    }
+
+    #[must_use]
+    pub fn ceil(&self) -> Number {
+        self.map(f64::ceil, BigRational::ceil, |value| value.with_scale_round(0, RoundingMode::Ceiling))
+    }
+
+    /// Rounds half-way cases away from zero.
+    #[must_use]
+    pub fn round(&self) -> Number {
+        self.map(f64::round, BigRational::round, |value| value.with_scale_round(0, RoundingMode::HalfUp))
+    }
+
+    /// Stays exact when both the numerator and the denominator are perfect squares. Negative and complex numbers
+    /// have complex roots, with a positive real part (or a positive imaginary part for negative numbers).
+    /// Quantities whose unit has no square root, such as metres, give NaN.
+    #[must_use]
+    pub fn sqrt(&self) -> Number {
+        if let Number::Quantity(value, unit) = self {
+            return match unit.pow(&BigRational::new(BigInt::one(), BigInt::from(2))) {
+                Some(unit) => Number::from_parts(value.sqrt(), &unit),
+                None => Number::Float(f64::NAN),
+            };
+        }
+        if self.is_complex() || self.is_negative() {
+            return Number::complex(self.to_complex().sqrt());
+        }
+        if let Number::Decimal(value, context) = self {
+            if let Some(root) = context.sqrt(value) {
+                return Number::Decimal(root, *context);
+            }
+        }
+        if let Number::Rational(value) = self {
+            if !value.is_negative() {
+                let numerator = value.numer().sqrt();
+                let denominator = value.denom().sqrt();
+                if &(&numerator * &numerator) == value.numer() && &(&denominator * &denominator) == value.denom() {
+                    return Number::Rational(BigRational::new(numerator, denominator));
+                }
+            }
+        }
+        Number::Float(self.to_f64().sqrt())
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if 0 is raised to a negative power, if the result isn't a real number, if it is a
+    /// decimal too large to be represented, or if the exponent doesn't give a valid unit.
+    pub fn pow(&self, exponent: &Number) -> Result<Number, InterpreterError> {
+        if exponent.is_quantity() {
+            return Err(InterpreterError::new(InterpreterErrorKind::InvalidUnitPower {
+                base: self.to_string(),
+                exponent: exponent.to_string(),
+            })
+            .with_help("exponents can't have a unit"));
+        }
+        if let Number::Quantity(value, unit) = self {
+            let unit = match exponent {
+                Number::Float(exponent) => BigRational::from_float(*exponent),
+                exponent => exponent.to_rational(),
+            }
+            .and_then(|exponent| unit.pow(&exponent))
+            .ok_or_else(|| {
+                InterpreterError::new(InterpreterErrorKind::InvalidUnitPower {
+                    base: self.to_string(),
+                    exponent: exponent.to_string(),
+                })
+                .with_help(format!("{unit}^{exponent} isn't a unit, units only have integer powers"))
+            })?;
+            return Ok(Number::from_parts(value.pow(exponent)?, &unit));
+        }
+        if self.is_zero() && exponent.is_negative() {
+            return Err(InterpreterErrorKind::NegativePowerOfZero.into());
+        }
+        let too_large = || {
+            InterpreterError::new(InterpreterErrorKind::Overflow { base: self.to_string(), exponent: exponent.to_string() })
+                .with_help("use a smaller exponent, or the float backend which overflows to inf")
+        };
+        let exact_exponent = exponent.to_exact_integer();
+        let integer_exponent = exponent
+            .to_integer()
+            .filter(|exponent| exponent.abs() <= MAX_EXACT_EXPONENT);
+        let result = match (self, exact_exponent) {
+            (Number::Complex(base), _) => Number::complex(match integer_exponent {
+                // Repeated multiplications keep i^2 exactly -1, where exp(2 * ln(i)) would give -1 + 1.2e-16i.
+                Some(exponent) => base.powi(exponent),
+                None => base.powc(exponent.to_complex()),
+            }),
+            _ if exponent.is_complex() => Number::complex(self.to_complex().powc(exponent.to_complex())),
+            // The principal value, as for sqrt: (-4)^0.5 is 2i. Square roots are kept exact rather than going through
+            // the polar form, which would give 1.2e-16+2i.
+            _ if self.is_negative() && exponent.to_f64().fract() != 0.0 => {
+                if (exponent.to_f64() - 0.5).abs() < f64::EPSILON {
+                    self.sqrt()
+                } else {
+                    Number::complex(self.to_complex().powf(exponent.to_f64()))
+                }
+            }
+            (Number::Rational(base), Some(exponent)) if exponent.abs() <= MAX_EXACT_EXPONENT => {
+                Number::Rational(Pow::pow(base, exponent))
+            }
+            // Squaring and multiplying only takes a few dozen steps, even for the largest exponents.
+            (Number::Decimal(base, context), Some(exponent)) => {
+                Number::Decimal(context.powi(base, exponent).ok_or_else(too_large)?, *context)
+            }
+            // x^y = exp(y * ln(x)), which is only real for positive values of x.
+            (Number::Decimal(base, context), None) if !base.is_negative() => {
+                match exponent.to_decimal(context) {
+                    Some(exponent) => Number::Decimal(context.powf(base, &exponent).ok_or_else(too_large)?, *context),
+                    None => Number::Float(self.to_f64().powf(exponent.to_f64())),
+                }
+            }
+            _ => Number::Float(self.to_f64().powf(exponent.to_f64())),
+        };
+        if result.is_nan() {
+            Err(InterpreterErrorKind::NotReal { base: self.to_string(), exponent: exponent.to_string() }.into())
+        } else {
+            Ok(result)
+        }
+    }
+}
+
+impl Add for Number {
+    type Output = Number;
+
+    fn add(self, rhs: Number) -> Number {
+        if self.is_quantity() || rhs.is_quantity() {
+            return self.add_quantities(&rhs, Add::add);
+        }
+        self.combine(rhs, |left, right| left + right, |left, right| left + right, DecimalContext::add, |left, right| left + right)
+    }
+}
+
+impl Sub for Number {
+    type Output = Number;
+
+    fn sub(self, rhs: Number) -> Number {
+        if self.is_quantity() || rhs.is_quantity() {
+            return self.add_quantities(&rhs, Sub::sub);
+        }
+        self.combine(rhs, |left, right| left - right, |left, right| left - right, DecimalContext::sub, |left, right| left - right)
+    }
+}
+
+impl Mul for Number {
+    type Output = Number;
+
+    fn mul(self, rhs: Number) -> Number {
+        if self.is_quantity() || rhs.is_quantity() {
+            let (left, left_unit) = self.into_parts();
+            let (right, right_unit) = rhs.into_parts();
+            return Number::from_parts(Mul::mul(left, right), &left_unit.multiply(&right_unit));
+        }
+        self.combine(rhs, |left, right| left * right, |left, right| left * right, DecimalContext::mul, |left, right| left * right)
+    }
+}
+
+/// Dividing an exact number by an exact zero panics, callers are expected to check for zero first.
+/// Quantities of different dimensions can't be added or subtracted: they give NaN, callers are expected to check
+/// the dimensions first.
+impl Div for Number {
+    type Output = Number;
+
+    fn div(self, rhs: Number) -> Number {
+        if self.is_quantity() || rhs.is_quantity() {
+            let (left, left_unit) = self.into_parts();
+            let (right, right_unit) = rhs.into_parts();
+            return Number::from_parts(Div::div(left, right), &left_unit.divide(&right_unit));
+        }
+        self.combine(rhs, |left, right| left / right, |left, right| left / right, DecimalContext::div, |left, right| left / right)
+    }
+}
+
+impl Neg for Number {
+    type Output = Number;
+
+    fn neg(self) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(0.0 - value), // so that -0 is displayed as 0
+            Number::Rational(value) => Number::Rational(-value),
+            Number::Decimal(value, context) => Number::Decimal(-value, context),
+            Number::Complex(value) => Number::Complex(-value),
+            Number::Quantity(value, unit) => Number::Quantity(Box::new(-*value), unit),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    #[allow(clippy::float_cmp)]
+    fn eq(&self, other: &Number) -> bool {
+        match (self, other) {
+            (Number::Quantity(..), _) | (_, Number::Quantity(..)) => {
+                matches!(self.values_in_same_unit(other), Some((left, right)) if left == right)
+            }
+            (Number::Decimal(left, _), Number::Decimal(right, _)) => left == right,
+            (Number::Complex(_), _) | (_, Number::Complex(_)) => self.to_complex() == other.to_complex(),
+            (Number::Float(_), _) | (_, Number::Float(_)) => self.to_f64() == other.to_f64(),
+            (left, right) => left.to_rational() == right.to_rational(),
+        }
+    }
+}
+
+impl PartialEq<f64> for Number {
+    #[allow(clippy::float_cmp)]
+    fn eq(&self, other: &f64) -> bool {
+        self.to_f64() == *other
+    }
+}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> {
+        match (self, other) {
+            // Quantities of different dimensions aren't ordered.
+            (Number::Quantity(..), _) | (_, Number::Quantity(..)) => {
+                let (left, right) = self.values_in_same_unit(other)?;
+                left.partial_cmp(&right)
+            }
+            (Number::Decimal(left, _), Number::Decimal(right, _)) => Some(left.cmp(right)),
+            // Complex numbers aren't ordered.
+            (Number::Complex(_), _) | (_, Number::Complex(_)) => (self == other).then_some(Ordering::Equal),
+            (Number::Float(_), _) | (_, Number::Float(_)) => self.to_f64().partial_cmp(&other.to_f64()),
+            (left, right) => left.to_rational().partial_cmp(&right.to_rational()),
+        }
+    }
+}
+
+// Rounds half-way cases away from zero.
+fn rational_to_decimal(value: &BigRational, decimals: usize) -> String {
+    let scale = BigRational::from_integer(Pow::pow(BigInt::from(10), decimals));
+    let scaled = (value * scale).round().to_integer();
+    let sign = if scaled.is_negative() { "-" } else { "" };
+    let digits = format!("{:0>width$}", scaled.abs(), width = decimals + 1);
+    let (integer_part, decimal_part) = digits.split_at(digits.len() - decimals);
+    if decimals == 0 {
+        format!("{sign}{integer_part}")
+    } else {
+        format!("{sign}{integer_part}.{decimal_part}")
+    }
+}
+
+// A fraction has a finite decimal expansion if its denominator only has 2 and 5 as prime factors, in which case
+// it needs as many decimals as the highest power of those.
+fn rational_to_exact_decimal(value: &BigRational) -> String {
+    let mut denominator = value.denom().clone();
+    let mut decimals = [2, 5].map(|factor| {
+        let factor = BigInt::from(factor);
+        let mut power = 0;
+        while (&denominator % &factor).is_zero() {
+            denominator /= &factor;
+            power += 1;
+        }
+        power
+    });
+    decimals.sort_unstable();
+    if denominator == BigInt::from(1) {
+        rational_to_decimal(value, decimals[1])
+    } else {
+        format!("{}...", rational_to_decimal(value, REPEATING_DECIMAL_DIGITS))
+    }
+}
+
+// Decimals are printed in full, unless that takes more zeros than significant digits, e.g. 2^1000 is 1.07...e301.
+fn needs_scientific_notation(value: &BigDecimal, context: &DecimalContext) -> bool {
+    let precision = i64::try_from(context.precision().get()).unwrap_or(i64::MAX);
+    !value.is_zero() && !(-precision..precision).contains(&value.order_of_magnitude())
+}
+
+// a+bi, leaving out the real part when it is zero and the 1 of the imaginary part, e.g. 3-4i, 2i or -i.
+fn write_rectangular(f: &mut fmt::Formatter<'_>, value: Complex64, precision: Option<usize>) -> fmt::Result {
+    let part = |part: f64| match precision {
+        Some(precision) => format!("{part:.precision$}"),
+        None => format!("{part}"),
+    };
+    let sign = if value.im.is_sign_negative() { "-" } else { "+" };
+    let imaginary = match part(value.im.abs()).as_str() {
+        "1" => String::new(),
+        digits => digits.to_string(),
+    };
+    match (value.re == 0.0, sign) {
+        (true, "+") => write!(f, "{imaginary}i"),
+        (true, _) => write!(f, "-{imaginary}i"),
+        (false, _) => write!(f, "{}{sign}{imaginary}i", part(value.re)),
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self, f.precision()) {
+            (Number::Float(value), Some(precision)) => write!(f, "{value:.precision$}"),
+            (Number::Float(value), None) => write!(f, "{value}"),
+            (Number::Rational(value), Some(precision)) => write!(f, "{}", rational_to_decimal(value, precision)),
+            (Number::Rational(value), None) if f.alternate() => write!(f, "{}", rational_to_exact_decimal(value)),
+            (Number::Rational(value), None) => write!(f, "{value}"),
+            (Number::Decimal(value, context), _) if needs_scientific_notation(value, context) => {
+                write!(f, "{}", value.to_scientific_notation())
+            }
+            (Number::Decimal(value, context), Some(precision)) => {
+                let scale = i64::try_from(precision).unwrap_or(i64::MAX);
+                write!(f, "{}", value.with_scale_round(scale, context.rounding()).to_plain_string())
+            }
+            (Number::Decimal(value, _), None) => write!(f, "{}", value.to_plain_string()),
+            (Number::Complex(value), precision) => write_rectangular(f, *value, precision),
+            (Number::Quantity(value, unit), _) => {
+                fmt::Display::fmt(value, f)?;
+                write!(f, " {unit}")
+            }
+        }
+    }
+}
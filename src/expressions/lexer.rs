@@ -1,14 +1,21 @@
 use regex::Regex;
-use crate::errors::lexer_error::LexerError;
+use crate::errors::diagnostic::Span;
+use crate::errors::lexer_error::{LexerError, LexerErrorKind};
 use crate::expressions::lexer::tokens::{Kind, Token};
+use crate::expressions::units::Unit;
 
 pub mod tokens {
+    use crate::errors::diagnostic::Span;
+
     #[derive(Debug, PartialEq, Clone)]
     pub enum Kind {
         Identifier,
         Operator,
         Separator,
         Literal,
+        Unit,
+        /// `;` or a newline, ending a statement.
+        Terminator,
     }
     #[derive(Debug, PartialEq, Clone)]
     pub struct Token {
@@ -26,6 +33,12 @@ pub mod tokens {
                 position,
             }
         }
+
+        /// The bytes of the source the token was read from.
+        #[must_use]
+        pub fn span(&self) -> Span {
+            Span::new(self.position, self.position + self.raw_value.len())
+        }
     }
 
     #[derive(Debug, Clone)]
@@ -50,13 +63,34 @@ pub mod tokens {
         }
 
         pub(crate) fn lookahead(&self) -> Option<&Token> {
-            self.buffer.get(self.cursor + 1)
+            self.peek(1)
+        }
+
+        pub(crate) fn peek(&self, offset: usize) -> Option<&Token> {
+            self.buffer.get(self.cursor + offset)
+        }
+
+        /// The span of the current token, or an empty span right after the last one when they have all been read.
+        pub(crate) fn curr_span(&self) -> Span {
+            match (self.curr(), self.buffer.last()) {
+                (Some(token), _) => token.span(),
+                (None, Some(last)) => Span::new(last.span().end, last.span().end),
+                (None, None) => Span::new(0, 0),
+            }
+        }
+
+        /// The span going from `start` to the end of the last token read.
+        pub(crate) fn span_from(&self, start: usize) -> Span {
+            let last = self.cursor.checked_sub(1).and_then(|index| self.buffer.get(index));
+            let end = last.map_or(start, |token| token.span().end);
+            Span::new(start, end.max(start))
         }
     }
 }
 
 pub struct Lexer {
-    token_regexs: [(Kind, Regex); 4],
+    token_regexs: [(Kind, Regex); 5],
+    unit_regex: Regex,
 }
 
 impl Lexer {
@@ -66,14 +100,16 @@ impl Lexer {
     pub fn new() -> Self {
         Self {
             token_regexs: [
-                (Kind::Identifier, Regex::new(r"^[a-zA-Z_]+").unwrap()),
+                (Kind::Identifier, Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*").unwrap()),
                 (
                     Kind::Literal,
-                    Regex::new(r"^\d+([.]\d+)?(e[+-]?\d+)?").unwrap(),
+                    Regex::new(r"^\d+([.]\d+)?(e[+-]?\d+)?(i\b)?").unwrap(),
                 ),
-                (Kind::Operator, Regex::new(r"^[+-/*^=]").unwrap()),
-                (Kind::Separator, Regex::new(r"^[()]").unwrap()),
+                (Kind::Operator, Regex::new(r"^(:=|[-+/*^=])").unwrap()),
+                (Kind::Separator, Regex::new(r"^[(),]").unwrap()),
+                (Kind::Terminator, Regex::new(r"^[;\n]").unwrap()),
             ],
+            unit_regex: Regex::new(r"^[a-zA-Zµ]+(\^-?\d+)?([*/][a-zA-Zµ]+(\^-?\d+)?)*").unwrap(),
         }
     }
 
@@ -83,11 +119,26 @@ impl Lexer {
     pub fn lex(&self, buffer: &str) -> Result<Vec<Token>, LexerError> {
         let mut token_vector: Vec<Token> = Vec::new();
         let mut cursor: usize = 0;
-        let buffer = buffer.trim();
+        // Only the end is trimmed, so that positions are those of the original buffer.
+        let buffer = buffer.trim_end();
+        // Newlines within parentheses don't end the statement, so that long calls can be split
+        let mut depth: usize = 0;
         while cursor < buffer.len() {
-            match self.next_token_in_buff(&buffer[cursor..], cursor) {
+            if Self::skip_blanks(&buffer[cursor..]).is_empty() {
+                break;
+            }
+            let token = self
+                .next_unit_or_keyword(&buffer[cursor..], cursor, token_vector.last())
+                .unwrap_or_else(|| self.next_token_in_buff(&buffer[cursor..], cursor));
+            match token {
                 Ok(token) => {
                     cursor = token.position + token.raw_value.len();
+                    match (&token.kind, token.raw_value.as_str()) {
+                        (Kind::Separator, "(") => depth += 1,
+                        (Kind::Separator, ")") => depth = depth.saturating_sub(1),
+                        (Kind::Terminator, "\n") if depth > 0 => continue,
+                        _ => {}
+                    }
                     token_vector.push(token);
                 }
                 Err(e) => {
@@ -104,14 +155,99 @@ impl Lexer {
         buffer: &str,
         buffer_start_offset: usize,
     ) -> Result<Token, LexerError> {
-        let trimmed_start_whitespaces = buffer.trim_start();
+        let trimmed_start_whitespaces = Self::skip_blanks(buffer);
         let delta = buffer_start_offset + buffer.len() - trimmed_start_whitespaces.len(); // we add to the offset the number of whitespace preceeding the potential actual token.
         for r in &self.token_regexs {
             if let Some(res) = r.1.find(trimmed_start_whitespaces) {
                 return Ok(Token::new(r.0.clone(), res.as_str().into(), delta));
             }
         }
-        Err(LexerError::new(format!("unknown token at position {delta}")))
+        let unknown = trimmed_start_whitespaces.chars().next().unwrap_or_default();
+        Err(LexerError::new(
+            LexerErrorKind::UnknownToken { found: unknown },
+            Span::new(delta, delta + unknown.len_utf8()),
+        ))
+    }
+
+    // Units are only recognised right after a number (`3 km`) or a conversion keyword (`to m/s`), so that variables
+    // named like units (m, s, h...) keep working everywhere else. `to` and `in` are conversion keywords after an
+    // operand, except for `in` right after a number when no unit follows it: `3 in` is three inches, and so is the
+    // first `in` of `3 in in cm`, whose second `in` is the keyword.
+    fn next_unit_or_keyword(
+        &self,
+        buffer: &str,
+        buffer_start_offset: usize,
+        previous: Option<&Token>,
+    ) -> Option<Result<Token, LexerError>> {
+        let trimmed_start_whitespaces = Self::skip_blanks(buffer);
+        let delta = buffer_start_offset + buffer.len() - trimmed_start_whitespaces.len();
+        let previous = previous?;
+        if previous.kind == Kind::Operator && matches!(previous.raw_value.as_str(), "to" | "in") {
+            // Without any word, the parser reports the missing unit.
+            let word = self.unit_regex.find(trimmed_start_whitespaces)?.as_str();
+            return Some(
+                self.unit_at(trimmed_start_whitespaces)
+                    .map(|unit| Token::new(Kind::Unit, unit.into(), delta))
+                    .ok_or_else(|| Self::unknown_unit(word, delta)),
+            );
+        }
+        let after_operand = matches!(previous.kind, Kind::Literal | Kind::Unit | Kind::Identifier)
+            || previous.raw_value == ")";
+        let word = self.token_regexs[0].1.find(trimmed_start_whitespaces).map(|word| word.as_str());
+        if let (true, Some(keyword @ ("to" | "in"))) = (after_operand, word) {
+            let rest = &trimmed_start_whitespaces[keyword.len()..];
+            if previous.kind != Kind::Literal || keyword == "to" || self.converts_to_unit(rest) {
+                return Some(Ok(Token::new(Kind::Operator, keyword.into(), delta)));
+            }
+        }
+        if previous.kind != Kind::Literal {
+            return None;
+        }
+        self.unit_at(trimmed_start_whitespaces)
+            .map(|unit| Ok(Token::new(Kind::Unit, unit.into(), delta)))
+    }
+
+    // Whether the buffer, following `in` right after a number, starts with the unit to convert to, rather than with a
+    // conversion of its own such as the `in cm` of `3 in in cm`.
+    fn converts_to_unit(&self, buffer: &str) -> bool {
+        let buffer = Self::skip_blanks(buffer);
+        self.unit_at(buffer).is_some_and(|unit| {
+            !matches!(unit, "to" | "in") || self.unit_at(Self::skip_blanks(&buffer[unit.len()..])).is_none()
+        })
+    }
+
+    // Skips the whitespaces and `#` comments at the start of the buffer, but not the newlines, which end statements.
+    fn skip_blanks(buffer: &str) -> &str {
+        let mut buffer = buffer.trim_start_matches(|c: char| c.is_whitespace() && c != '\n');
+        while let Some(comment) = buffer.strip_prefix('#') {
+            buffer = comment.trim_start_matches(|c: char| c != '\n');
+        }
+        buffer
+    }
+
+    fn unknown_unit(word: &str, delta: usize) -> LexerError {
+        let reason = match word.parse::<Unit>() {
+            Err(err) => err,
+            Ok(_) => String::from("it is followed by other characters"),
+        };
+        LexerError::new(LexerErrorKind::UnknownUnit { unit: word.into(), reason }, Span::new(delta, delta + word.len()))
+            .with_help("units are written like m, km/h or kg*m/s^2")
+    }
+
+    // Returns the longest unit expression at the start of the buffer, stopping between two factors if needed so
+    // that `m/x` is metres divided by x.
+    fn unit_at<'a>(&self, buffer: &'a str) -> Option<&'a str> {
+        let candidate = self.unit_regex.find(buffer)?.as_str();
+        let ends_a_word = buffer[candidate.len()..]
+            .chars()
+            .next()
+            .is_none_or(|next| !next.is_alphanumeric() && next != '_');
+        let full = ends_a_word.then_some(candidate.len());
+        let boundaries = candidate.match_indices(['*', '/']).map(|(index, _)| index).rev();
+        full.into_iter()
+            .chain(boundaries)
+            .map(|end| &candidate[..end])
+            .find(|unit| unit.parse::<Unit>().is_ok())
     }
 }
 
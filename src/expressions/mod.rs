@@ -1,3 +1,6 @@
+pub mod builtins;
 pub mod interpreter;
 pub mod lexer;
 pub mod parser;
+pub mod prelude;
+pub mod units;
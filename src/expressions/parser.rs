@@ -1,13 +1,38 @@
-use crate::errors::parser_error::SyntaxError;
+use crate::errors::parser_error::{SyntaxError, SyntaxErrorKind};
 use crate::expressions::lexer::tokens::Kind::{Operator, Separator};
 use crate::expressions::lexer::tokens::{Kind, Token, TokenStream};
-use crate::expressions::parser::ast::Expression;
-use crate::expressions::parser::ast::Expression::{Assignment, Eof, Literal, UnaryMinus, UnaryPlus, Variable};
+use crate::errors::diagnostic::Span;
+use crate::expressions::parser::ast::{Expression, Numeral, Statement};
+use crate::expressions::{builtins, prelude};
+use crate::expressions::parser::ast::Expression::{
+    Assignment, Call, Conversion, Eof, FunctionDefinition, Literal, ParenthesisExpression, Program, Quantity, Variable,
+};
+use crate::expressions::parser::operators::{
+    InfixOperator, CONVERSION_PRECEDENCE, IMPLICIT_MULTIPLICATION, UNIT_PRECEDENCE,
+};
+use crate::expressions::units::Unit;
 
 pub mod ast {
+    use std::fmt;
+    use std::str::FromStr;
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+    use num_traits::{One, Pow, Signed, ToPrimitive};
+    use crate::errors::diagnostic::Span;
+    use crate::expressions::units::Unit;
+
+    // Literals with a bigger exponent than that are refused, as their exact value would be huge.
+    const MAX_LITERAL_EXPONENT: i64 = 10_000;
+
     #[derive(Debug, PartialEq, Clone)]
     pub enum Expression {
+        /// `a = 1; a + 1`: statements evaluated in order, the value of the last one being the value of the program.
+        Program(Vec<Statement>),
         Assignment(String, Box<Expression>),
+        /// `x := a + 1`: a variable bound to the value the expression has when it is bound, see
+        /// [`Binding`](crate::expressions::interpreter::Binding).
+        Binding(String, Box<Expression>),
+        FunctionDefinition(String, Vec<String>, Box<Expression>),
 
         Addition(Box<Expression>, Box<Expression>),
         Subtraction(Box<Expression>, Box<Expression>),
@@ -17,15 +42,348 @@ pub mod ast {
         ParenthesisExpression(Box<Expression>),
 
         Multiplication(Box<Expression>, Box<Expression>),
-        Division(Box<Expression>, Box<Expression>),
-        Literal(f64),
-        Variable(String),
+        /// The span, as the ones of the calls, variables and powers, is where the expression is in its source, which
+        /// the errors it raises point at.
+        Division(Box<Expression>, Box<Expression>, Span),
+        Power(Box<Expression>, Box<Expression>, Span),
+        Call(String, Vec<Expression>, Span),
+        /// `3 km`: a value in a unit.
+        Quantity(Box<Expression>, Unit),
+        /// `60 mph to m/s`: a quantity converted to another unit of the same dimension.
+        Conversion(Box<Expression>, Unit),
+        Literal(Numeral),
+        Variable(String, Span),
         Eof,
     }
+
+    /// A statement of a [`Expression::Program`], with the bytes of the source it was read from.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Statement {
+        pub expression: Expression,
+        pub span: Span,
+    }
+
+    impl Expression {
+        // How tightly the expression holds together when written out, matching the precedences of
+        // `operators`: an operand that binds looser than its operator needs parentheses.
+        fn precedence(&self) -> u8 {
+            match self {
+                Expression::Program(_)
+                | Expression::Assignment(_, _)
+                | Expression::Binding(_, _)
+                | Expression::FunctionDefinition(_, _, _) => 0,
+                Expression::Conversion(_, _) => 5,
+                Expression::Addition(_, _) | Expression::Subtraction(_, _) => 10,
+                Expression::Multiplication(_, _) | Expression::Division(_, _, _) => 20,
+                Expression::UnaryPlus(_) | Expression::UnaryMinus(_) => 30,
+                Expression::Quantity(_, _) => 35,
+                Expression::Power(_, _, _) => 40,
+                Expression::ParenthesisExpression(_)
+                | Expression::Call(_, _, _)
+                | Expression::Literal(_)
+                | Expression::Variable(_, _)
+                | Expression::Eof => 50,
+            }
+        }
+
+        // Writes the operand, within parentheses if it doesn't bind at least as tightly as `precedence`.
+        fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, precedence: u8) -> fmt::Result {
+            if self.precedence() < precedence {
+                write!(f, "({self})")
+            } else {
+                write!(f, "{self}")
+            }
+        }
+
+        fn fmt_binary(f: &mut fmt::Formatter<'_>, left: &Self, operator: &str, right: &Self, precedence: u8) -> fmt::Result {
+            left.fmt_operand(f, precedence)?;
+            write!(f, "{operator}")?;
+            right.fmt_operand(f, precedence + 1)
+        }
+    }
+
+    /// Writes the expression back as source code, which parses to the same expression: `2(1 + x)` is written
+    /// `2 * (1 + x)`.
+    impl fmt::Display for Expression {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Expression::Program(statements) => {
+                    let statements: Vec<String> =
+                        statements.iter().map(|statement| statement.expression.to_string()).collect();
+                    write!(f, "{}", statements.join("; "))
+                }
+                Expression::Assignment(name, expr) => write!(f, "{name} = {expr}"),
+                Expression::Binding(name, expr) => write!(f, "{name} := {expr}"),
+                Expression::FunctionDefinition(name, parameters, body) => {
+                    write!(f, "{name}({}) = {body}", parameters.join(", "))
+                }
+                Expression::Addition(left, right) => Self::fmt_binary(f, left, " + ", right, 10),
+                Expression::Subtraction(left, right) => Self::fmt_binary(f, left, " - ", right, 10),
+                Expression::Multiplication(left, right) => Self::fmt_binary(f, left, " * ", right, 20),
+                Expression::Division(left, right, _) => Self::fmt_binary(f, left, " / ", right, 20),
+                Expression::Power(base, exponent, _) => {
+                    // Right associative: `2^3^2` is `2^(3^2)`
+                    base.fmt_operand(f, 41)?;
+                    write!(f, "^")?;
+                    exponent.fmt_operand(f, 40)
+                }
+                Expression::UnaryPlus(expr) => {
+                    write!(f, "+")?;
+                    expr.fmt_operand(f, 30)
+                }
+                Expression::UnaryMinus(expr) => {
+                    write!(f, "-")?;
+                    expr.fmt_operand(f, 30)
+                }
+                Expression::ParenthesisExpression(expr) => write!(f, "({expr})"),
+                Expression::Call(name, arguments, _) => {
+                    let arguments: Vec<String> = arguments.iter().map(ToString::to_string).collect();
+                    write!(f, "{name}({})", arguments.join(", "))
+                }
+                Expression::Quantity(expr, unit) => {
+                    expr.fmt_operand(f, 35)?;
+                    write!(f, " {unit}")
+                }
+                Expression::Conversion(expr, unit) => {
+                    expr.fmt_operand(f, 5)?;
+                    write!(f, " to {unit}")
+                }
+                Expression::Literal(numeral) => write!(f, "{numeral}"),
+                Expression::Variable(name, _) => write!(f, "{name}"),
+                Expression::Eof => Ok(()),
+            }
+        }
+    }
+
+    /// A number as written in the source. It is kept as an exact fraction (`0.1` is exactly 1/10),
+    /// the interpreter then converts it to the representation of its numeric backend.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct Numeral {
+        value: BigRational,
+        imaginary: bool,
+    }
+
+    impl Numeral {
+        /// The imaginary number `value * i`.
+        #[must_use]
+        pub fn imaginary(value: i64) -> Self {
+            Numeral {
+                imaginary: true,
+                ..value.into()
+            }
+        }
+
+        /// The value of the literal, without the imaginary unit.
+        #[must_use]
+        pub fn value(&self) -> &BigRational {
+            &self.value
+        }
+
+        #[must_use]
+        pub fn is_imaginary(&self) -> bool {
+            self.imaginary
+        }
+
+        #[must_use]
+        pub fn to_f64(&self) -> f64 {
+            self.value.to_f64().unwrap_or(f64::NAN)
+        }
+    }
+
+    impl From<i64> for Numeral {
+        fn from(value: i64) -> Self {
+            Numeral {
+                value: BigRational::from_integer(BigInt::from(value)),
+                imaginary: false,
+            }
+        }
+    }
+
+    /// Writes the numeral as a decimal literal: `1.5e-3` is written `0.0015`.
+    impl fmt::Display for Numeral {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            // Literals are written in base 10, so the denominator only has 2 and 5 as prime factors and
+            // the value is an integer once multiplied by a big enough power of 10.
+            let ten = BigRational::from_integer(BigInt::from(10));
+            let mut digits = self.value.clone();
+            let mut decimals = 0;
+            while !digits.denom().is_one() && decimals <= MAX_LITERAL_EXPONENT {
+                digits *= ten.clone();
+                decimals += 1;
+            }
+            if !digits.denom().is_one() {
+                write!(f, "{}", self.value)?;
+            } else if decimals == 0 {
+                write!(f, "{}", digits.numer())?;
+            } else {
+                let integer = digits.numer().abs().to_string();
+                let decimals = usize::try_from(decimals).unwrap_or(usize::MAX);
+                let integer = format!("{integer:0>width$}", width = decimals + 1);
+                let (integer_part, decimal_part) = integer.split_at(integer.len() - decimals);
+                let sign = if digits.is_negative() { "-" } else { "" };
+                write!(f, "{sign}{integer_part}.{decimal_part}")?;
+            }
+            if self.imaginary {
+                write!(f, "i")?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Parses decimal literals such as `12`, `0.25` or `1.5e-3`, and imaginary ones such as `4i`.
+    impl FromStr for Numeral {
+        type Err = String;
+
+        fn from_str(literal: &str) -> Result<Self, Self::Err> {
+            let (number, imaginary) = match literal.strip_suffix('i') {
+                Some(number) => (number, true),
+                None => (literal, false),
+            };
+            let (mantissa, exponent) = match number.split_once(['e', 'E']) {
+                Some((mantissa, exponent)) => (
+                    mantissa,
+                    exponent
+                        .parse::<i64>()
+                        .map_err(|err| format!("invalid exponent in {literal}: {err}"))?,
+                ),
+                None => (number, 0),
+            };
+            if exponent.abs() > MAX_LITERAL_EXPONENT {
+                return Err(format!("exponent of {literal} is too large"));
+            }
+            let (integer_part, decimal_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+            let digits = format!("{integer_part}{decimal_part}")
+                .parse::<BigInt>()
+                .map_err(|err| format!("invalid number {literal}: {err}"))?;
+            // Both values are bounded by the checks above, so this can't overflow.
+            let exponent = exponent - i64::try_from(decimal_part.len()).unwrap_or(i64::MAX);
+            let scale = Pow::pow(BigInt::from(10), exponent.unsigned_abs());
+            let value = if exponent < 0 {
+                BigRational::new(digits, scale)
+            } else {
+                BigRational::from_integer(digits * scale)
+            };
+            Ok(Numeral { value, imaginary })
+        }
+    }
 }
+
+/// Every operator the parser knows about, with its precedence, associativity and the AST node it builds.
+/// Adding an operator to the language should only require adding a line to one of these tables.
+pub mod operators {
+    use crate::errors::diagnostic::Span;
+    use crate::expressions::lexer::tokens::{Kind, Token};
+    use crate::expressions::parser::ast::Expression;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Associativity {
+        Left,
+        Right,
+    }
+
+    pub struct PrefixOperator {
+        pub symbol: &'static str,
+        pub precedence: u8,
+        pub build: fn(Box<Expression>) -> Expression,
+    }
+
+    pub struct InfixOperator {
+        pub symbol: &'static str,
+        pub precedence: u8,
+        pub associativity: Associativity,
+        /// Builds the node from the operands and the span of the whole operation.
+        pub build: fn(Box<Expression>, Box<Expression>, Span) -> Expression,
+    }
+
+    pub struct PostfixOperator {
+        pub symbol: &'static str,
+        pub precedence: u8,
+        pub build: fn(Box<Expression>) -> Expression,
+    }
+
+    // Precedences are spaced by 10 so that associativity can be encoded as a +1 on one side.
+    pub const PREFIX_OPERATORS: &[PrefixOperator] = &[
+        PrefixOperator { symbol: "+", precedence: 30, build: Expression::UnaryPlus },
+        PrefixOperator { symbol: "-", precedence: 30, build: Expression::UnaryMinus },
+    ];
+
+    pub const INFIX_OPERATORS: &[InfixOperator] = &[
+        InfixOperator { symbol: "+", precedence: 10, associativity: Associativity::Left, build: |l, r, _| Expression::Addition(l, r) },
+        InfixOperator { symbol: "-", precedence: 10, associativity: Associativity::Left, build: |l, r, _| Expression::Subtraction(l, r) },
+        InfixOperator { symbol: "*", precedence: 20, associativity: Associativity::Left, build: |l, r, _| Expression::Multiplication(l, r) },
+        InfixOperator { symbol: "/", precedence: 20, associativity: Associativity::Left, build: Expression::Division },
+        InfixOperator { symbol: "^", precedence: 40, associativity: Associativity::Right, build: Expression::Power },
+    ];
+
+    pub const POSTFIX_OPERATORS: &[PostfixOperator] = &[];
+
+    /// A unit after an operand, as in `3 km`. It binds tighter than the prefix operators, so that `-3 m` is
+    /// `-(3 m)`, but looser than `^`: `2^3 m` is 8 m.
+    pub const UNIT_PRECEDENCE: u8 = 35;
+
+    /// `x to unit` (or `x in unit`) applies to everything on its left: `1 km + 1 m to m` is 1001 m.
+    pub const CONVERSION_KEYWORDS: &[&str] = &["to", "in"];
+    pub const CONVERSION_PRECEDENCE: u8 = 5;
+
+    /// `2(3)` is read as `2 * 3`: an opening parenthesis right after an operand behaves like an infix `*`.
+    pub const IMPLICIT_MULTIPLICATION: InfixOperator = InfixOperator {
+        symbol: "(",
+        precedence: 20,
+        associativity: Associativity::Left,
+        build: |left, right, _| Expression::Multiplication(left, right),
+    };
+
+    impl InfixOperator {
+        /// Returns the (left, right) binding powers of the operator.
+        /// The side with the highest power wins when two operators of the same precedence compete for an operand.
+        #[must_use]
+        pub fn binding_powers(&self) -> (u8, u8) {
+            match self.associativity {
+                Associativity::Left => (self.precedence, self.precedence + 1),
+                Associativity::Right => (self.precedence + 1, self.precedence),
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn prefix(token: &Token) -> Option<&'static PrefixOperator> {
+        if token.kind != Kind::Operator {
+            return None;
+        }
+        PREFIX_OPERATORS.iter().find(|operator| operator.symbol == token.raw_value)
+    }
+
+    #[must_use]
+    pub fn infix(token: &Token) -> Option<&'static InfixOperator> {
+        match token.kind {
+            Kind::Operator => INFIX_OPERATORS.iter().find(|operator| operator.symbol == token.raw_value),
+            Kind::Separator if token.raw_value == IMPLICIT_MULTIPLICATION.symbol => Some(&IMPLICIT_MULTIPLICATION),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn is_conversion(token: &Token) -> bool {
+        token.kind == Kind::Operator && CONVERSION_KEYWORDS.contains(&token.raw_value.as_str())
+    }
+
+    #[must_use]
+    pub fn postfix(token: &Token) -> Option<&'static PostfixOperator> {
+        if token.kind != Kind::Operator {
+            return None;
+        }
+        POSTFIX_OPERATORS.iter().find(|operator| operator.symbol == token.raw_value)
+    }
+}
+
 #[derive(Clone)]
 pub struct Parser {
     symbol_table: Vec<String>,
+    function_table: Vec<String>,
+    // Parameters of the function whose body is being parsed
+    scope: Vec<String>,
+    // Name of the function whose body is being parsed, so that it can call itself
+    defining: Option<String>,
     tokens: TokenStream,
 }
 
@@ -34,149 +392,204 @@ impl Parser {
     pub fn new() -> Self {
         Self {
             symbol_table: Vec::new(),
+            function_table: Vec::new(),
+            scope: Vec::new(),
+            defining: None,
             tokens: TokenStream::new(Vec::new()),
         }
     }
 
-    fn parse_factor(&mut self) -> Result<Expression, SyntaxError> {
+    /// Forgets a variable or function name, which is then an unknown symbol until it is defined again.
+    pub fn forget(&mut self, name: &str) {
+        self.symbol_table.retain(|symbol| symbol != name);
+        self.function_table.retain(|function| function != name);
+    }
+
+    /// Forgets every variable and function name.
+    pub fn clear(&mut self) {
+        self.symbol_table.clear();
+        self.function_table.clear();
+    }
+
+    // An error about the current token, or about the end of the line if there is none left.
+    fn error(&self, kind: SyntaxErrorKind) -> SyntaxError {
+        SyntaxError::new(kind, self.tokens.curr_span())
+    }
+
+    // An error about the current token, or about the missing one if the end of the line has been reached.
+    fn unexpected(&self, expected: &str) -> SyntaxError {
+        match self.tokens.curr() {
+            Some(token) => self.error(SyntaxErrorKind::UnexpectedToken {
+                expected: expected.into(),
+                found: token.raw_value.clone(),
+            }),
+            None => self.error(SyntaxErrorKind::UnexpectedEnd { expected: expected.into() }),
+        }
+    }
+
+    fn expect_closing_parenthesis(&mut self) -> Result<(), SyntaxError> {
+        match self.tokens.curr() {
+            Some(token) if token.kind == Separator && token.raw_value.as_str() == ")" => {
+                self.tokens.next();
+                Ok(())
+            }
+            _ => Err(self.unexpected("')'").with_help("every '(' needs a matching ')'")),
+        }
+    }
+
+    // Parses whatever can start an expression: literals, variables, parenthesis expressions and prefix operators.
+    fn parse_prefix(&mut self) -> Result<Expression, SyntaxError> {
         match self.tokens.curr() {
             Some(token) => match token.kind {
-                Operator => match token.raw_value.as_str() {
-                    "+" => {
-                        self.tokens.next();
-                        Ok(UnaryPlus(Box::new(self.parse_factor()?)))
-                    }
-                    "-" => {
+                Operator => match operators::prefix(token) {
+                    Some(operator) => {
                         self.tokens.next();
-                        Ok(UnaryMinus(Box::new(self.parse_factor()?)))
+                        let operand = self.parse_expression(operator.precedence)?;
+                        Ok((operator.build)(Box::new(operand)))
                     }
-                    _ => Err(SyntaxError::new(format!("Unexpected operator {token:?}"))),
+                    None => Err(self.unexpected("a value")),
                 },
                 Kind::Literal => {
-                    let literal_value = token
-                        .raw_value
-                        .parse::<f64>()
-                        .map_err(|_| SyntaxError::new(String::from("Couldn't parse token to an integer")))?;
+                    let literal_value = token.raw_value.parse::<Numeral>().map_err(|reason| {
+                        self.error(SyntaxErrorKind::InvalidLiteral { literal: token.raw_value.clone(), reason })
+                    })?;
                     self.tokens.next();
                     Ok(Literal(literal_value))
                 }
-
                 Kind::Identifier => {
-                    if self.symbol_table.contains(&token.raw_value) {
-                        let res = Ok(Variable(token.raw_value.clone()));
+                    let is_function = builtins::find(&token.raw_value).is_some()
+                        || self.function_table.contains(&token.raw_value)
+                        || self.defining.as_ref() == Some(&token.raw_value);
+                    if is_function && self.is_call() {
+                        let (name, start) = (token.raw_value.clone(), token.span().start);
+                        self.tokens.next();
+                        self.parse_call(name, start)
+                    } else if self.scope.contains(&token.raw_value)
+                        || self.symbol_table.contains(&token.raw_value)
+                        || prelude::find(&token.raw_value).is_some()
+                    {
+                        let res = Ok(Variable(token.raw_value.clone(), token.span()));
                         self.tokens.next();
                         res
                     } else {
-                        Err(SyntaxError::new(format!("Couldn't find symbol {}", token.raw_value)))
+                        Err(self
+                            .error(SyntaxErrorKind::UnknownSymbol { name: token.raw_value.clone() })
+                            .with_help(format!("define it first, e.g. {} = 1", token.raw_value)))
                     }
                 }
                 Separator => {
                     if let "(" = token.raw_value.as_str() {
                         self.tokens.next();
-                        let expr = self.parse_expr()?;
-                        match self.tokens.curr() {
-                            Some(token) => {
-                                if token.kind == Separator && token.raw_value.as_str() == ")" {
-                                    self.tokens.next();
-                                    Ok(Expression::ParenthesisExpression(Box::new(expr)))
-                                } else {
-                                    Err(SyntaxError::new(format!(
-                                        "Expected ')', got {:?}, which is definitely not ')'",
-                                        token.raw_value
-                                    )))
-                                }
-                            }
-                            None => Err(SyntaxError::new(String::from("Expected ')', got nothing bruuuuh"))),
-                        }
+                        let expr = self.parse_expression(0)?;
+                        self.expect_closing_parenthesis()?;
+                        Ok(ParenthesisExpression(Box::new(expr)))
                     } else {
-                        Err(SyntaxError::new(format!("Expected a '(' got {:?}", token.raw_value)))
+                        Err(self.unexpected("a value"))
                     }
                 }
+                Kind::Unit => Err(self.unexpected("a value before the unit")),
+                Kind::Terminator => Err(self.unexpected("a value")),
             },
-            None => Err(SyntaxError::new(String::from("Expected a factor, got nothing"))),
+            None => Err(self.unexpected("a value")),
         }
     }
 
-    fn parse_term_prime(&mut self, left: Expression) -> Result<Expression, SyntaxError> {
-        match self.tokens.curr() {
-            Some(token) => match token.kind {
-                Operator => match token.raw_value.as_str() {
-                    "*" => {
-                        self.tokens.next();
-                        let factor = self.parse_factor()?;
-                        self.parse_term_prime(Expression::Multiplication(
-                            Box::from(left),
-                            Box::from(factor),
-                        ))
-                    }
-                    "/" => {
-                        self.tokens.next();
-                        let factor = self.parse_factor()?;
-                        self.parse_term_prime(Expression::Division(
-                            Box::from(left),
-                            Box::from(factor),
-                        ))
-                    }
-                    _ => Ok(left),
-                },
-                Separator => match token.raw_value.as_str() {
-                    "(" => {
-                      self.tokens.next();
-                        let expr = self.parse_factor()?;
-                        match self.tokens.curr() {
-                            Some(token) => if let ")" = token.raw_value.as_str() {
-                                Ok(Expression::Multiplication(
-                                    Box::from(left),
-                                    Box::from(expr),
-                                ))
-                            } else {
-                                Err(SyntaxError::new(format!("Expected ')' got {token:?}")))
-                            }
-                            None => Err(SyntaxError::new(String::from("Expected ')' got nothing.")))
-                        }
-                    },
-                    ")" => Ok(left),
-                    _ => Err(SyntaxError::new(String::from("??")))  // means we would have a tokenization problem...
+    fn is_call(&self) -> bool {
+        matches!(self.tokens.lookahead(), Some(token) if token.kind == Separator && token.raw_value == "(")
+    }
+
+    // Parses a comma separated list of arguments, the function name, starting at `start`, having already been consumed.
+    fn parse_call(&mut self, name: String, start: usize) -> Result<Expression, SyntaxError> {
+        self.tokens.next();
+        let mut arguments = Vec::new();
+        if matches!(self.tokens.curr(), Some(token) if token.kind == Separator && token.raw_value == ")") {
+            self.tokens.next();
+            return Ok(Call(name, arguments, self.tokens.span_from(start)));
+        }
+        loop {
+            arguments.push(self.parse_expression(0)?);
+            match self.tokens.curr() {
+                Some(token) if token.kind == Separator && token.raw_value == "," => {
+                    self.tokens.next();
                 }
-                _ => Err(SyntaxError::new(format!("unexpected token {token:?}")))
-            },
-            None => Ok(left),
+                _ => {
+                    self.expect_closing_parenthesis()?;
+                    return Ok(Call(name, arguments, self.tokens.span_from(start)));
+                }
+            }
         }
     }
 
-    fn parse_term(&mut self) -> Result<Expression, SyntaxError> {
-        self.parse_factor()
-            .and_then(|factor| self.parse_term_prime(factor))
+    // Parses the right operand of the operator, `left` starting at `start`.
+    fn parse_infix(
+        &mut self,
+        left: Expression,
+        operator: &InfixOperator,
+        start: usize,
+    ) -> Result<Expression, SyntaxError> {
+        self.tokens.next();
+        if operator.symbol == IMPLICIT_MULTIPLICATION.symbol {
+            // The parenthesis has been consumed as the operator, so its content is not wrapped
+            // in a ParenthesisExpression.
+            let right = self.parse_expression(0)?;
+            self.expect_closing_parenthesis()?;
+            Ok((operator.build)(Box::new(left), Box::new(right), self.tokens.span_from(start)))
+        } else {
+            let (_, right_binding_power) = operator.binding_powers();
+            let right = self.parse_expression(right_binding_power)?;
+            Ok((operator.build)(Box::new(left), Box::new(right), self.tokens.span_from(start)))
+        }
     }
 
-    fn parse_expr_prime(&mut self, left: Expression) -> Result<Expression, SyntaxError> {
-        match self.tokens.curr() {
-            Some(token) => match token.kind {
-                Operator => match token.raw_value.as_str() {
-                    "+" => {
-                        self.tokens.next();
-                        let right = self.parse_term()?;
-                        self.parse_expr_prime(Expression::Addition(Box::new(left), Box::new(right)))
-                    }
-                    "-" => {
-                        self.tokens.next();
-                        let right = self.parse_term()?;
-                        self.parse_expr_prime(Expression::Subtraction(
-                            Box::new(left),
-                            Box::new(right),
-                        ))
-                    }
-                    _ => Ok(left),
-                },
-                _ => Ok(left),
-            },
-            None => Ok(left),
+    // Pratt parsing loop: keeps extending `left` as long as the next operator binds at least as tightly as
+    // `min_binding_power`.
+    fn parse_expression(&mut self, min_binding_power: u8) -> Result<Expression, SyntaxError> {
+        let start = self.tokens.curr_span().start;
+        let mut left = self.parse_prefix()?;
+        while let Some(token) = self.tokens.curr() {
+            if token.kind == Kind::Unit {
+                if UNIT_PRECEDENCE < min_binding_power {
+                    break;
+                }
+                let unit = self.parse_unit(token)?;
+                self.tokens.next();
+                left = Quantity(Box::new(left), unit);
+            } else if operators::is_conversion(token) {
+                if CONVERSION_PRECEDENCE < min_binding_power {
+                    break;
+                }
+                let keyword = token.raw_value.clone();
+                self.tokens.next();
+                let unit = match self.tokens.curr() {
+                    Some(token) if token.kind == Kind::Unit => self.parse_unit(token)?,
+                    _ => return Err(self.unexpected(&format!("a unit after '{keyword}'"))),
+                };
+                self.tokens.next();
+                left = Conversion(Box::new(left), unit);
+            } else if let Some(operator) = operators::postfix(token) {
+                if operator.precedence < min_binding_power {
+                    break;
+                }
+                self.tokens.next();
+                left = (operator.build)(Box::new(left));
+            } else if let Some(operator) = operators::infix(token) {
+                let (left_binding_power, _) = operator.binding_powers();
+                if left_binding_power < min_binding_power {
+                    break;
+                }
+                left = self.parse_infix(left, operator, start)?;
+            } else {
+                break;
+            }
         }
+        Ok(left)
     }
 
-    fn parse_expr(&mut self) -> Result<Expression, SyntaxError> {
-        self.parse_term()
-            .and_then(|term| self.parse_expr_prime(term))
+    fn parse_unit(&self, token: &Token) -> Result<Unit, SyntaxError> {
+        token
+            .raw_value
+            .parse::<Unit>()
+            .map_err(|reason| self.error(SyntaxErrorKind::InvalidUnit { unit: token.raw_value.clone(), reason }))
     }
 
     fn parse_assignment(&mut self) -> Result<Expression, SyntaxError> {
@@ -186,55 +599,159 @@ impl Parser {
                 match self.tokens.next() {
                     Some(assignment_token)
                     if assignment_token.kind == Operator
-                        && assignment_token.raw_value.as_str() == "=" =>
+                        && matches!(assignment_token.raw_value.as_str(), "=" | ":=") =>
                         {
+                            let eager = assignment_token.raw_value == ":=";
                             self.tokens.next();
-                            if !self.symbol_table.contains(&idt_token_clone.raw_value) {
-                                self.symbol_table.push(idt_token_clone.clone().raw_value);
+                            let expression = Box::new(self.parse_expression(0)?);
+                            if eager {
+                                Ok(Expression::Binding(idt_token_clone.raw_value, expression))
+                            } else {
+                                Ok(Assignment(idt_token_clone.raw_value, expression))
                             }
-                            Ok(Assignment(
-                                idt_token_clone.raw_value,
-                                Box::new(self.parse_expr()?),
-                            ))
                         }
-                    _ => Err(SyntaxError::new(String::from("Expected an = after the identifier"))),
+                    _ => Err(self.unexpected("an = or := after the identifier")),
                 }
             }
-            _ => Err(SyntaxError::new(String::from("Expected an identifier"))),
+            _ => Err(self.unexpected("an identifier")),
         }
     }
 
+    fn parse_parameters(&mut self) -> Result<Vec<String>, SyntaxError> {
+        let mut parameters: Vec<String> = Vec::new();
+        loop {
+            self.tokens.next();
+            match self.tokens.curr() {
+                Some(token) if token.kind == Kind::Identifier => {
+                    if parameters.contains(&token.raw_value) {
+                        return Err(self.error(SyntaxErrorKind::DuplicateParameter { name: token.raw_value.clone() }));
+                    }
+                    parameters.push(token.raw_value.clone());
+                }
+                Some(token) if token.kind == Separator && token.raw_value == ")" && parameters.is_empty() => {
+                    self.tokens.next();
+                    return Ok(parameters);
+                }
+                _ => return Err(self.unexpected("a parameter name")),
+            }
+            match self.tokens.next() {
+                Some(token) if token.kind == Separator && token.raw_value == "," => {}
+                Some(token) if token.kind == Separator && token.raw_value == ")" => {
+                    self.tokens.next();
+                    return Ok(parameters);
+                }
+                _ => return Err(self.unexpected("',' or ')'")),
+            }
+        }
+    }
 
+    fn parse_function_definition(&mut self) -> Result<Expression, SyntaxError> {
+        let name = match self.tokens.curr() {
+            Some(token) if token.kind == Kind::Identifier => token.raw_value.clone(),
+            _ => return Err(self.unexpected("a function name")),
+        };
+        self.tokens.next();
+        let parameters = self.parse_parameters()?;
+        match self.tokens.curr() {
+            Some(token) if token.kind == Operator && token.raw_value == "=" => {
+                self.tokens.next();
+            }
+            _ => return Err(self.unexpected("an = after the parameter list")),
+        }
+        self.scope.clone_from(&parameters);
+        self.defining = Some(name.clone());
+        let body = self.parse_expression(0);
+        self.scope.clear();
+        self.defining = None;
+        Ok(FunctionDefinition(name, parameters, Box::new(body?)))
+    }
+
+    // Looks for `name(a, b, ...) =` without consuming anything.
+    fn is_function_definition(&self) -> bool {
+        let is_separator =
+            |token: Option<&Token>, separator: &str| matches!(token, Some(token) if token.kind == Separator && token.raw_value == separator);
+        if !matches!(self.tokens.curr(), Some(token) if token.kind == Kind::Identifier)
+            || !is_separator(self.tokens.peek(1), "(")
+        {
+            return false;
+        }
+        let mut offset = 2;
+        while let Some(token) = self.tokens.peek(offset) {
+            if token.kind == Kind::Identifier || is_separator(Some(token), ",") {
+                offset += 1;
+            } else {
+                break;
+            }
+        }
+        is_separator(self.tokens.peek(offset), ")")
+            && matches!(self.tokens.peek(offset + 1), Some(token) if token.kind == Operator && token.raw_value == "=")
+    }
+
+    fn is_assignment(&self) -> bool {
+        matches!(
+            (self.tokens.curr(), self.tokens.lookahead()),
+            (Some(identifier), Some(operator))
+                if identifier.kind == Kind::Identifier && operator.kind == Operator
+                    && matches!(operator.raw_value.as_str(), "=" | ":=")
+        )
+    }
+
+    /// Parses statements separated by `;` or newlines. A single statement is returned as is, several are
+    /// returned as a [`Expression::Program`], and no statement at all as [`Expression::Eof`].
+    ///
     /// # Errors
     ///
     /// Will return an error if it fails creating AST from tokens
     pub fn parse(&mut self, line: &[Token]) -> Result<Expression, SyntaxError> {
-        self.tokens = TokenStream::new(line.to_vec());
-        if let Some(token) = self.tokens.curr() {
-            match token.kind {
-                Kind::Identifier => match self.tokens.lookahead() {
-                    Some(lookahead) => match lookahead.kind {
-                        Operator => {
-                            if let "=" = lookahead.raw_value.as_str() {
-                                self.parse_assignment()
-                            } else {
-                                self.parse_expr()
-                            }
-                        }
-                        _ => Err(SyntaxError::new(format!(
-                            "Expected calculus or assignment operator, got {token:?}"
-                        ))),
-                    },
-                    None => self.parse_expr(),
-                },
-                Separator | Kind::Literal => self.parse_expr(),
-                Operator => match token.raw_value.as_str() {
-                    "+" | "-" => self.parse_expr(),
-                    _ => Err(SyntaxError::new(format!("Expected + or -, got {token:?}"))),
-                },
-            }
+        let mut statements = line
+            .split(|token| token.kind == Kind::Terminator)
+            .filter(|tokens| !tokens.is_empty())
+            .map(|tokens| {
+                let span = Span::new(tokens[0].span().start, tokens[tokens.len() - 1].span().end);
+                Ok(Statement { expression: self.parse_statement(tokens)?, span })
+            })
+            .collect::<Result<Vec<Statement>, SyntaxError>>()?;
+        Ok(match statements.len() {
+            0 => Eof,
+            1 => statements.remove(0).expression,
+            _ => Program(statements),
+        })
+    }
+
+    fn parse_statement(&mut self, tokens: &[Token]) -> Result<Expression, SyntaxError> {
+        self.tokens = TokenStream::new(tokens.to_vec());
+        let expression = if self.is_assignment() {
+            self.parse_assignment()?
+        } else if self.is_function_definition() {
+            self.parse_function_definition()?
         } else {
-            Ok(Eof)
+            self.parse_expression(0)?
+        };
+        if self.tokens.curr().is_some() {
+            return Err(self
+                .unexpected("an operator or the end of the line")
+                .with_help("operators such as '*' can't be left out between two values"));
+        }
+        self.declare(&expression);
+        Ok(expression)
+    }
+
+    // Names are only known once the statement defining them has been parsed, so that a failing one has no effect.
+    fn declare(&mut self, statement: &Expression) {
+        match statement {
+            Assignment(name, _) | Expression::Binding(name, _) => {
+                self.function_table.retain(|function| function != name);
+                if !self.symbol_table.contains(name) {
+                    self.symbol_table.push(name.clone());
+                }
+            }
+            FunctionDefinition(name, _, _) => {
+                self.symbol_table.retain(|symbol| symbol != name);
+                if !self.function_table.contains(name) {
+                    self.function_table.push(name.clone());
+                }
+            }
+            _ => {}
         }
     }
 }
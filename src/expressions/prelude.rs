@@ -0,0 +1,44 @@
+use std::f64::consts;
+use bigdecimal::{BigDecimal, One};
+use num_complex::Complex64;
+use crate::expressions::interpreter::decimal::DecimalContext;
+use crate::expressions::interpreter::number::{Backend, Number};
+
+/// Named constants available everywhere without being defined. They are protected: they can't be
+/// reassigned, nor used as a function or parameter name.
+pub struct Constant {
+    pub name: &'static str,
+    pub value: Complex64,
+    // Computes the constant up to the precision of the decimal backend, if it is a decimal number at all.
+    decimal: Option<fn(&DecimalContext) -> BigDecimal>,
+}
+
+impl Constant {
+    /// The value of the constant with the given backend. None of them is rational, so they are floats unless
+    /// decimals are used, or complex for the imaginary unit.
+    #[must_use]
+    pub fn evaluate(&self, backend: Backend) -> Number {
+        match (backend, self.decimal) {
+            (Backend::Decimal(context), Some(decimal)) => Number::Decimal(decimal(&context), context),
+            _ => Number::complex(self.value),
+        }
+    }
+}
+
+pub const CONSTANTS: &[Constant] = &[
+    Constant { name: "pi", value: Complex64::new(consts::PI, 0.0), decimal: Some(DecimalContext::pi) },
+    Constant { name: "e", value: Complex64::new(consts::E, 0.0), decimal: Some(|context| context.exp(&BigDecimal::one()).unwrap_or_default()) },
+    Constant { name: "tau", value: Complex64::new(consts::TAU, 0.0), decimal: Some(|context| context.round(&context.pi().double())) },
+    Constant { name: "phi", value: Complex64::new(1.618_033_988_749_895, 0.0), decimal: Some(|context| {
+        let root = context.sqrt(&BigDecimal::from(5)).unwrap_or_default();
+        context.round(&(BigDecimal::one() + root).half())
+    }) },
+    Constant { name: "inf", value: Complex64::new(f64::INFINITY, 0.0), decimal: None },
+    Constant { name: "nan", value: Complex64::new(f64::NAN, 0.0), decimal: None },
+    Constant { name: "i", value: Complex64::I, decimal: None },
+];
+
+#[must_use]
+pub fn find(name: &str) -> Option<&'static Constant> {
+    CONSTANTS.iter().find(|constant| constant.name == name)
+}
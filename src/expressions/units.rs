@@ -0,0 +1,289 @@
+use std::fmt;
+use std::str::FromStr;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Pow, ToPrimitive};
+use serde::{Serialize, Serializer};
+
+const DIMENSION_NAMES: [&str; 6] = ["length", "mass", "time", "current", "temperature", "amount"];
+
+/// Exponents of the SI base dimensions: length, mass, time, electric current, temperature and amount of
+/// substance. A speed is length/time, so its dimension is `[1, 0, -1, 0, 0, 0]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dimension([i32; 6]);
+
+impl Dimension {
+    #[must_use]
+    pub const fn new(length: i32, mass: i32, time: i32, current: i32, temperature: i32, amount: i32) -> Self {
+        Dimension([length, mass, time, current, temperature, amount])
+    }
+
+    #[must_use]
+    pub fn is_dimensionless(&self) -> bool {
+        self.0.iter().all(|exponent| *exponent == 0)
+    }
+
+    fn combined(self, other: Dimension, exponent: i32) -> Dimension {
+        let mut exponents = self.0;
+        for (combined, other) in exponents.iter_mut().zip(other.0) {
+            *combined += other * exponent;
+        }
+        Dimension(exponents)
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_dimensionless() {
+            return write!(f, "dimensionless");
+        }
+        let factors = DIMENSION_NAMES.iter().zip(self.0).filter(|(_, exponent)| *exponent != 0);
+        write_factors(f, factors.map(|(name, exponent)| (name.to_string(), exponent)))
+    }
+}
+
+// Written as displayed, e.g. `length/time`, as the values of errors are
+impl Serialize for Dimension {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+const DIMENSIONLESS: Dimension = Dimension::new(0, 0, 0, 0, 0, 0);
+const LENGTH: Dimension = Dimension::new(1, 0, 0, 0, 0, 0);
+const MASS: Dimension = Dimension::new(0, 1, 0, 0, 0, 0);
+const TIME: Dimension = Dimension::new(0, 0, 1, 0, 0, 0);
+const CURRENT: Dimension = Dimension::new(0, 0, 0, 1, 0, 0);
+const TEMPERATURE: Dimension = Dimension::new(0, 0, 0, 0, 1, 0);
+const AMOUNT: Dimension = Dimension::new(0, 0, 0, 0, 0, 1);
+const AREA: Dimension = Dimension::new(2, 0, 0, 0, 0, 0);
+const VOLUME: Dimension = Dimension::new(3, 0, 0, 0, 0, 0);
+const FREQUENCY: Dimension = Dimension::new(0, 0, -1, 0, 0, 0);
+const SPEED: Dimension = Dimension::new(1, 0, -1, 0, 0, 0);
+const FORCE: Dimension = Dimension::new(1, 1, -2, 0, 0, 0);
+const ENERGY: Dimension = Dimension::new(2, 1, -2, 0, 0, 0);
+const POWER: Dimension = Dimension::new(2, 1, -3, 0, 0, 0);
+const PRESSURE: Dimension = Dimension::new(-1, 1, -2, 0, 0, 0);
+const CHARGE: Dimension = Dimension::new(0, 0, 1, 1, 0, 0);
+const VOLTAGE: Dimension = Dimension::new(2, 1, -3, -1, 0, 0);
+const RESISTANCE: Dimension = Dimension::new(2, 1, -3, -2, 0, 0);
+
+/// A decimal prefix, such as the `k` of `km` which stands for 10^3.
+#[derive(Debug, PartialEq)]
+pub struct Prefix {
+    pub symbol: &'static str,
+    pub power_of_ten: i32,
+}
+
+pub const PREFIXES: &[Prefix] = &[
+    Prefix { symbol: "G", power_of_ten: 9 },
+    Prefix { symbol: "M", power_of_ten: 6 },
+    Prefix { symbol: "k", power_of_ten: 3 },
+    Prefix { symbol: "c", power_of_ten: -2 },
+    Prefix { symbol: "m", power_of_ten: -3 },
+    Prefix { symbol: "µ", power_of_ten: -6 },
+    Prefix { symbol: "u", power_of_ten: -6 },
+    Prefix { symbol: "n", power_of_ten: -9 },
+];
+
+/// A named unit, worth `scale` (a fraction, so that conversions can be exact) of the SI units of its dimension.
+#[derive(Debug, PartialEq)]
+pub struct UnitDefinition {
+    pub symbol: &'static str,
+    pub name: &'static str,
+    pub dimension: Dimension,
+    scale: (i64, i64),
+    // Whether the unit accepts the decimal prefixes, as in km or mL.
+    prefixable: bool,
+}
+
+// The kilogram is the SI unit of mass, but prefixes apply to the gram.
+pub const UNITS: &[UnitDefinition] = &[
+    UnitDefinition { symbol: "m", name: "metre", dimension: LENGTH, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "g", name: "gram", dimension: MASS, scale: (1, 1000), prefixable: true },
+    UnitDefinition { symbol: "s", name: "second", dimension: TIME, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "A", name: "ampere", dimension: CURRENT, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "K", name: "kelvin", dimension: TEMPERATURE, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "mol", name: "mole", dimension: AMOUNT, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "Hz", name: "hertz", dimension: FREQUENCY, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "N", name: "newton", dimension: FORCE, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "J", name: "joule", dimension: ENERGY, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "W", name: "watt", dimension: POWER, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "Pa", name: "pascal", dimension: PRESSURE, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "C", name: "coulomb", dimension: CHARGE, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "V", name: "volt", dimension: VOLTAGE, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "ohm", name: "ohm", dimension: RESISTANCE, scale: (1, 1), prefixable: true },
+    UnitDefinition { symbol: "ha", name: "hectare", dimension: AREA, scale: (10_000, 1), prefixable: false },
+    UnitDefinition { symbol: "L", name: "litre", dimension: VOLUME, scale: (1, 1000), prefixable: true },
+    UnitDefinition { symbol: "cal", name: "calorie", dimension: ENERGY, scale: (4184, 1000), prefixable: true },
+    UnitDefinition { symbol: "min", name: "minute", dimension: TIME, scale: (60, 1), prefixable: false },
+    UnitDefinition { symbol: "h", name: "hour", dimension: TIME, scale: (3600, 1), prefixable: false },
+    UnitDefinition { symbol: "day", name: "day", dimension: TIME, scale: (86400, 1), prefixable: false },
+    UnitDefinition { symbol: "in", name: "inch", dimension: LENGTH, scale: (254, 10_000), prefixable: false },
+    UnitDefinition { symbol: "ft", name: "foot", dimension: LENGTH, scale: (3048, 10_000), prefixable: false },
+    UnitDefinition { symbol: "yd", name: "yard", dimension: LENGTH, scale: (9144, 10_000), prefixable: false },
+    UnitDefinition { symbol: "mi", name: "mile", dimension: LENGTH, scale: (1_609_344, 1000), prefixable: false },
+    UnitDefinition { symbol: "acre", name: "acre", dimension: AREA, scale: (40_468_564_224, 10_000_000), prefixable: false },
+    UnitDefinition { symbol: "mph", name: "mile per hour", dimension: SPEED, scale: (1_609_344, 3_600_000), prefixable: false },
+    UnitDefinition { symbol: "lb", name: "pound", dimension: MASS, scale: (45_359_237, 100_000_000), prefixable: false },
+    UnitDefinition { symbol: "oz", name: "ounce", dimension: MASS, scale: (45_359_237, 1_600_000_000), prefixable: false },
+    UnitDefinition { symbol: "lbf", name: "pound-force", dimension: FORCE, scale: (44_482_216_152_605, 10_000_000_000_000), prefixable: false },
+    UnitDefinition { symbol: "psi", name: "pound per square inch", dimension: PRESSURE, scale: (44_482_216_152_605, 6_451_600_000), prefixable: false },
+];
+
+/// Finds a unit by its symbol, possibly prefixed. Exact symbols win, so `min` is a minute rather than a
+/// milli-inch, and `mi` a mile.
+#[must_use]
+pub fn find(symbol: &str) -> Option<(Option<&'static Prefix>, &'static UnitDefinition)> {
+    if let Some(definition) = UNITS.iter().find(|definition| definition.symbol == symbol) {
+        return Some((None, definition));
+    }
+    PREFIXES.iter().find_map(|prefix| {
+        let rest = symbol.strip_prefix(prefix.symbol)?;
+        UNITS
+            .iter()
+            .find(|definition| definition.prefixable && definition.symbol == rest)
+            .map(|definition| (Some(prefix), definition))
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Factor {
+    prefix: Option<&'static Prefix>,
+    definition: &'static UnitDefinition,
+    exponent: i32,
+}
+
+impl Factor {
+    fn symbol(&self) -> String {
+        format!("{}{}", self.prefix.map_or("", |prefix| prefix.symbol), self.definition.symbol)
+    }
+
+    fn is_same_unit(&self, other: &Factor) -> bool {
+        self.prefix == other.prefix && self.definition == other.definition
+    }
+}
+
+/// A product of units raised to integer powers, such as `km/h` or `kg*m^2/s^2`. The empty product is the unit of
+/// plain numbers.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Unit {
+    factors: Vec<Factor>,
+}
+
+impl Unit {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.factors.is_empty()
+    }
+
+    #[must_use]
+    pub fn dimension(&self) -> Dimension {
+        self.factors
+            .iter()
+            .fold(DIMENSIONLESS, |dimension, factor| dimension.combined(factor.definition.dimension, factor.exponent))
+    }
+
+    /// How many SI units of its dimension one of this unit is worth, e.g. 1000 for km or 5/18 for km/h.
+    #[must_use]
+    pub fn scale(&self) -> BigRational {
+        self.factors.iter().fold(BigRational::one(), |scale, factor| {
+            let (numerator, denominator) = factor.definition.scale;
+            let power_of_ten = factor.prefix.map_or(0, |prefix| prefix.power_of_ten);
+            let factor_scale = BigRational::new(BigInt::from(numerator), BigInt::from(denominator))
+                * Pow::pow(BigRational::from_integer(BigInt::from(10)), power_of_ten);
+            scale * Pow::pow(factor_scale, factor.exponent)
+        })
+    }
+
+    /// Multiplies the units, merging the factors that are the same unit: `m/s` times `s` is `m`.
+    #[must_use]
+    pub fn multiply(&self, other: &Unit) -> Unit {
+        let mut factors = self.factors.clone();
+        for factor in &other.factors {
+            match factors.iter_mut().find(|existing| existing.is_same_unit(factor)) {
+                Some(existing) => existing.exponent += factor.exponent,
+                None => factors.push(factor.clone()),
+            }
+        }
+        factors.retain(|factor| factor.exponent != 0);
+        Unit { factors }
+    }
+
+    #[must_use]
+    pub fn divide(&self, other: &Unit) -> Unit {
+        self.multiply(&other.pow(&-BigRational::one()).unwrap_or_default())
+    }
+
+    /// Returns `None` if an exponent of the result isn't an integer, e.g. for the square root of a metre.
+    #[must_use]
+    pub fn pow(&self, exponent: &BigRational) -> Option<Unit> {
+        let factors = self
+            .factors
+            .iter()
+            .map(|factor| {
+                let exponent = exponent * BigInt::from(factor.exponent);
+                exponent.is_integer().then_some(())?;
+                Some(Factor { exponent: exponent.to_integer().to_i32()?, ..factor.clone() })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Unit { factors: factors.into_iter().filter(|factor| factor.exponent != 0).collect() })
+    }
+}
+
+// Factors with a positive exponent come first, then each one with a negative exponent as a division: a*b^2/c/d.
+// Without any positive exponent, negative ones are kept as is: s^-1.
+fn write_factors(f: &mut fmt::Formatter<'_>, factors: impl Iterator<Item = (String, i32)>) -> fmt::Result {
+    let (numerator, denominator): (Vec<_>, Vec<_>) = factors.partition(|(_, exponent)| *exponent > 0);
+    let power = |(symbol, exponent): &(String, i32)| match exponent {
+        1 => symbol.clone(),
+        exponent => format!("{symbol}^{exponent}"),
+    };
+    if numerator.is_empty() {
+        return write!(f, "{}", denominator.iter().map(power).collect::<Vec<_>>().join("*"));
+    }
+    write!(f, "{}", numerator.iter().map(power).collect::<Vec<_>>().join("*"))?;
+    for (symbol, exponent) in denominator {
+        write!(f, "/{}", power(&(symbol, -exponent)))?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_factors(f, self.factors.iter().map(|factor| (factor.symbol(), factor.exponent)))
+    }
+}
+
+/// Parses unit expressions such as `km`, `m/s^2` or `kg*m^2/s^2`.
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let mut unit = Unit::default();
+        let mut rest = expression;
+        let mut sign = 1;
+        loop {
+            let end = rest.find(['*', '/']).unwrap_or(rest.len());
+            let (symbol, exponent) = match rest[..end].split_once('^') {
+                Some((symbol, exponent)) => (
+                    symbol,
+                    exponent
+                        .parse::<i32>()
+                        .map_err(|err| format!("invalid exponent in {expression}: {err}"))?,
+                ),
+                None => (&rest[..end], 1),
+            };
+            let (prefix, definition) = find(symbol).ok_or_else(|| format!("unknown unit {symbol}"))?;
+            let factor = Unit { factors: vec![Factor { prefix, definition, exponent: sign * exponent }] };
+            unit = unit.multiply(&factor);
+            match rest[end..].chars().next() {
+                Some(operator) => {
+                    sign = if operator == '/' { -1 } else { 1 };
+                    rest = &rest[end + 1..];
+                }
+                None => return Ok(unit),
+            }
+        }
+    }
+}
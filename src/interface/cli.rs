@@ -1,11 +1,57 @@
-use clap::Args;
+use std::num::NonZeroU64;
+use clap::{Args, Subcommand, ValueEnum};
+use crate::errors::Error;
+use crate::expressions::interpreter::decimal::{DecimalContext, RoundingMode};
+use crate::expressions::interpreter::number::{Backend, Notation};
 
 /// Simple mathematical expression program
 #[derive(clap::Parser)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, subcommand_negates_reqs = true)]
 pub struct Cli {
     #[command(flatten)]
     pub mode: Mode,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub format: Format,
+    /// Go on after a failing statement of a script, and sum the failures up at the end
+    #[arg(short, long, global = true)]
+    pub keep_going: bool,
+    /// How numbers are represented: fast floats, exact fractions, or decimals with a given precision
+    /// [default: float, or decimal if --precision or --rounding is given]
+    #[arg(short, long, value_enum, global = true)]
+    pub backend: Option<BackendKind>,
+    /// Significant digits of decimal numbers [default: 50]
+    #[arg(short, long, global = true)]
+    pub precision: Option<NonZeroU64>,
+    /// How decimal numbers are rounded [default: half-even]
+    #[arg(short, long, value_enum, global = true)]
+    pub rounding: Option<Rounding>,
+}
+
+/// How results are written.
+#[derive(Args, Debug)]
+pub struct Format {
+    /// Print only the results, without the expressions, for use in shell scripts
+    #[arg(short, long, visible_alias = "raw")]
+    pub quiet: bool,
+    /// How results and errors are written: as text, or as a JSON object per statement, one per line
+    #[arg(short, long, value_enum, default_value_t = Output::Text, global = true)]
+    pub output: Output,
+    /// Display exact results as decimals instead of fractions
+    #[arg(short, long, global = true)]
+    pub decimal: bool,
+    /// Display complex results in polar form, r∠θ with θ in radians
+    #[arg(long, global = true)]
+    pub polar: bool,
+}
+
+impl Format {
+    /// The notation numbers are displayed in.
+    #[must_use]
+    pub fn notation(&self) -> Notation {
+        Notation { decimal: self.decimal, polar: self.polar }
+    }
 }
 
 #[derive(Args, Debug)]
@@ -15,4 +61,103 @@ pub struct Mode {
     pub interactive: bool,
     #[arg(short, long)]
     pub exec: Option<String>,
+    /// Run a script, - to read it from the standard input
+    #[arg(value_name = "FILE")]
+    pub script: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run a script: statements separated by newlines or `;`, with `#` comments
+    Run {
+        /// The script to run, - to read it from the standard input
+        file: String,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackendKind {
+    Float,
+    Rational,
+    Decimal,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    Text,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Rounding {
+    /// Round to the nearest, ties to the even digit
+    HalfEven,
+    /// Round to the nearest, ties away from zero
+    HalfUp,
+    /// Round to the nearest, ties toward zero
+    HalfDown,
+    /// Round toward zero (truncate)
+    Down,
+    /// Round away from zero
+    Up,
+    /// Round toward positive infinity
+    Ceiling,
+    /// Round toward negative infinity
+    Floor,
+}
+
+impl From<Rounding> for RoundingMode {
+    fn from(rounding: Rounding) -> Self {
+        match rounding {
+            Rounding::HalfEven => RoundingMode::HalfEven,
+            Rounding::HalfUp => RoundingMode::HalfUp,
+            Rounding::HalfDown => RoundingMode::HalfDown,
+            Rounding::Down => RoundingMode::Down,
+            Rounding::Up => RoundingMode::Up,
+            Rounding::Ceiling => RoundingMode::Ceiling,
+            Rounding::Floor => RoundingMode::Floor,
+        }
+    }
+}
+
+impl Cli {
+    /// # Errors
+    ///
+    /// Will return an error if a precision or a rounding mode is given along with a backend other than decimal.
+    pub fn backend(&self) -> Result<Backend, String> {
+        let decimal_options = self.precision.is_some() || self.rounding.is_some();
+        match self.backend {
+            Some(BackendKind::Float) | None if !decimal_options => Ok(Backend::Float),
+            Some(BackendKind::Rational) if !decimal_options => Ok(Backend::Rational),
+            Some(BackendKind::Decimal) | None => {
+                let defaults = DecimalContext::default();
+                Ok(Backend::Decimal(DecimalContext::new(
+                    self.precision.unwrap_or(defaults.precision()),
+                    self.rounding.map_or(defaults.rounding(), RoundingMode::from),
+                )))
+            }
+            Some(backend) => Err(format!(
+                "--precision and --rounding only apply to the decimal backend, not to {backend:?}."
+            )),
+        }
+    }
+}
+
+/// The exit status of the program when something else than the expressions failed, such as reading a file.
+pub const EXIT_FAILURE: u8 = 1;
+/// The exit status of the program when an expression has an unknown character or unit.
+pub const EXIT_LEXER_ERROR: u8 = 3;
+/// The exit status of the program when an expression is malformed or uses something undefined.
+pub const EXIT_SYNTAX_ERROR: u8 = 4;
+/// The exit status of the program when an expression can't be evaluated, such as a division by zero.
+pub const EXIT_INTERPRETER_ERROR: u8 = 5;
+
+/// The exit status of the program failing with the error. Usage errors exit with 2, as set by clap.
+#[must_use]
+pub fn exit_code(error: &Error) -> u8 {
+    match error {
+        Error::Lexer(_) => EXIT_LEXER_ERROR,
+        Error::Syntax(_) => EXIT_SYNTAX_ERROR,
+        Error::Interpreter(_) => EXIT_INTERPRETER_ERROR,
+    }
 }
@@ -0,0 +1,352 @@
+use std::borrow::Cow;
+use std::env;
+use std::io::{self, IsTerminal};
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use crate::errors::diagnostic::{Diagnostic, Span};
+use crate::errors::parser_error::SyntaxErrorKind;
+use crate::errors::Error;
+use crate::expressions::interpreter::Interpreter;
+use crate::expressions::lexer::tokens::{Kind, Token};
+use crate::expressions::lexer::Lexer;
+use crate::expressions::parser::Parser;
+use crate::expressions::{builtins, prelude};
+use crate::interface::repl::commands::COMMANDS;
+
+/// How a part of the line being typed is highlighted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Literal,
+    Unit,
+    Operator,
+    Separator,
+    Variable,
+    Function,
+    Command,
+    /// Unknown characters, units and names, and unmatched parentheses
+    Error,
+}
+
+impl Style {
+    // The ANSI escape sequence starting the style.
+    fn color(self) -> &'static str {
+        match self {
+            Style::Literal => "\x1b[36m",
+            Style::Unit => "\x1b[32m",
+            Style::Operator => "\x1b[33m",
+            Style::Separator => "\x1b[35m",
+            Style::Variable => "\x1b[0m",
+            Style::Function => "\x1b[34m",
+            Style::Command => "\x1b[1m",
+            Style::Error => "\x1b[1;4;31m",
+        }
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+
+/// Whether the terminal should be coloured: only when the standard output is one, and `NO_COLOR` is not set,
+/// see <https://no-color.org>.
+#[must_use]
+pub fn use_colors() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// What the line editor of the REPL knows about the session: the names it can complete, and how to highlight the
+/// line being typed.
+pub struct EditorHelper {
+    lexer: Lexer,
+    parser: Parser,
+    variables: Vec<String>,
+    // Name and signature, e.g. `f` and `f(x, y)`
+    functions: Vec<(String, String)>,
+    colors: bool,
+}
+
+impl EditorHelper {
+    #[must_use]
+    pub fn new(colors: bool) -> Self {
+        Self {
+            lexer: Lexer::new(),
+            parser: Parser::new(),
+            variables: Vec::new(),
+            functions: Vec::new(),
+            colors,
+        }
+    }
+
+    /// Takes the names defined so far in the session, to be called before reading each line.
+    pub fn update(&mut self, parser: &Parser, interpreter: &Interpreter) {
+        self.parser = parser.clone();
+        self.variables = interpreter.variables().into_iter().map(|(name, _)| name.to_string()).collect();
+        self.functions = interpreter
+            .functions()
+            .into_iter()
+            .map(|(name, parameters, _)| (name.to_string(), format!("{name}({})", parameters.join(", "))))
+            .collect();
+    }
+
+    /// The candidates for the word before `pos`, along with where that word starts.
+    /// Commands are completed at the start of a line, names everywhere else: variables and constants, then
+    /// functions, whose candidates display their signature and insert the opening parenthesis.
+    #[must_use]
+    pub fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<Pair>) {
+        let before = &line[..pos];
+        if before.starts_with(':') && !before.contains(char::is_whitespace) {
+            let candidates = COMMANDS
+                .iter()
+                .filter(|command| command.name.starts_with(before))
+                .map(|command| Pair {
+                    display: format!("{} {}", command.name, command.arguments).trim_end().to_string(),
+                    replacement: command.name.to_string(),
+                })
+                .collect();
+            return (0, candidates);
+        }
+        let start = before
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+            .last()
+            .map_or(pos, |(index, _)| index);
+        let word = &before[start..];
+        // Numbers and units, which follow numbers, are not completed
+        let after_number = before[..start].trim_end().ends_with(|c: char| c.is_ascii_digit());
+        if word.is_empty() || word.starts_with(|c: char| c.is_ascii_digit()) || after_number {
+            return (pos, Vec::new());
+        }
+        // The commands taking names only complete the ones of the session
+        let naming = [":del", ":deps", ":dependents"].iter().any(|command| line.split_whitespace().next() == Some(*command));
+        let variables = self
+            .variables
+            .iter()
+            .map(String::as_str)
+            .chain(prelude::CONSTANTS.iter().map(|constant| constant.name).filter(|_| !naming))
+            .map(|name| Pair { display: name.to_string(), replacement: name.to_string() });
+        let functions = self
+            .functions
+            .iter()
+            .map(|(name, signature)| (name.as_str(), signature.as_str()))
+            .chain(builtins::BUILTINS.iter().map(|builtin| (builtin.name, builtin.signature)).filter(|_| !naming))
+            .map(|(name, signature)| Pair {
+                display: signature.to_string(),
+                replacement: if naming { name.to_string() } else { format!("{name}(") },
+            });
+        let mut candidates: Vec<Pair> = variables
+            .chain(functions)
+            .filter(|candidate| candidate.replacement.starts_with(word))
+            .collect();
+        candidates.sort_by(|a, b| a.display.cmp(&b.display));
+        (start, candidates)
+    }
+
+    fn is_variable(&self, name: &str) -> bool {
+        self.variables.iter().any(|variable| variable == name) || prelude::find(name).is_some()
+    }
+
+    fn is_function(&self, name: &str) -> bool {
+        self.functions.iter().any(|(function, _)| function == name) || builtins::find(name).is_some()
+    }
+
+    /// The style of each part of the line, in order. Whatever is left out, such as whitespaces, is not highlighted.
+    #[must_use]
+    pub fn styles(&self, line: &str) -> Vec<(Span, Style)> {
+        let mut styles = Vec::new();
+        let mut start = 0;
+        if line.starts_with(':') {
+            let end = line.find(char::is_whitespace).unwrap_or(line.len());
+            styles.push((Span::new(0, end), Style::Command));
+            start = end;
+        }
+        let (tokens, errors) = self.lex(line, start);
+        styles.extend(errors.into_iter().map(|span| (span, Style::Error)));
+        let (mut open_parentheses, mut defined) = (Vec::new(), Vec::new());
+        for statement in tokens.split(|token| token.kind == Kind::Terminator) {
+            self.style_statement(statement, &mut defined, &mut styles, &mut open_parentheses);
+        }
+        styles.extend(
+            tokens
+                .iter()
+                .filter(|token| token.kind == Kind::Terminator)
+                .map(|token| (token.span(), Style::Separator)),
+        );
+        for index in open_parentheses {
+            styles[index].1 = Style::Error;
+        }
+        styles.sort_by_key(|(span, _)| span.start);
+        styles
+    }
+
+    // Adds the styles of the tokens of a statement, keeping track of the names defined by the previous statements
+    // of the line and of the parentheses still open.
+    fn style_statement(
+        &self,
+        tokens: &[Token],
+        defined_before: &mut Vec<String>,
+        styles: &mut Vec<(Span, Style)>,
+        open_parentheses: &mut Vec<usize>,
+    ) {
+        // The names on the left of `=` or `:=` are being defined, and the parameters of a function are known in its
+        // body
+        let definition_end = tokens
+            .iter()
+            .position(|token| token.kind == Kind::Operator && matches!(token.raw_value.as_str(), "=" | ":="));
+        let parameters: Vec<&str> = tokens[..definition_end.unwrap_or(0)]
+            .iter()
+            .skip(1)
+            .filter(|token| token.kind == Kind::Identifier)
+            .map(|token| token.raw_value.as_str())
+            .collect();
+        for (index, token) in tokens.iter().enumerate() {
+            let is_call = matches!(tokens.get(index + 1), Some(next) if next.kind == Kind::Separator && next.raw_value == "(");
+            let defined = definition_end.is_some_and(|end| index < end) || defined_before.contains(&token.raw_value);
+            let style = match token.kind {
+                Kind::Literal => Style::Literal,
+                Kind::Unit => Style::Unit,
+                Kind::Operator => Style::Operator,
+                Kind::Separator | Kind::Terminator => match token.raw_value.as_str() {
+                    "(" => {
+                        open_parentheses.push(styles.len());
+                        Style::Separator
+                    }
+                    ")" if open_parentheses.pop().is_none() => Style::Error,
+                    _ => Style::Separator,
+                },
+                Kind::Identifier if is_call && (defined || self.is_function(&token.raw_value)) => Style::Function,
+                Kind::Identifier
+                    if !is_call
+                        && (defined || parameters.contains(&token.raw_value.as_str()) || self.is_variable(&token.raw_value)) =>
+                {
+                    Style::Variable
+                }
+                Kind::Identifier => Style::Error,
+            };
+            styles.push((token.span(), style));
+        }
+        if let (Some(_), Some(name)) = (definition_end, tokens.first()) {
+            defined_before.push(name.raw_value.clone());
+        }
+    }
+
+    // The tokens of the line from `start`, and the spans that can't be read as tokens. Lexing resumes after an error
+    // so that the rest of the line is still highlighted.
+    fn lex(&self, line: &str, mut start: usize) -> (Vec<Token>, Vec<Span>) {
+        let (mut tokens, mut errors) = (Vec::new(), Vec::new());
+        let shifted = |tokens: Vec<Token>, offset: usize| {
+            tokens.into_iter().map(move |token| Token::new(token.kind, token.raw_value, token.position + offset))
+        };
+        while start < line.len() {
+            match self.lexer.lex(&line[start..]) {
+                Ok(line_tokens) => {
+                    tokens.extend(shifted(line_tokens, start));
+                    break;
+                }
+                Err(err) => {
+                    let span = err.span().unwrap_or(Span::new(0, line.len() - start));
+                    // What comes before the error is fine
+                    tokens.extend(shifted(self.lexer.lex(&line[start..start + span.start]).unwrap_or_default(), start));
+                    errors.push(Span::new(start + span.start, start + span.end.max(span.start + 1)));
+                    start += span.end.max(span.start + 1);
+                }
+            }
+        }
+        (tokens, errors)
+    }
+
+    /// The error of the line, if it is complete but can't be evaluated, such as `1 $ 2` or `f(1))`.
+    /// Lines that only miss their end, or whose last name is still being typed, are not errors.
+    #[must_use]
+    pub fn error_hint(&self, line: &str) -> Option<String> {
+        if line.trim().is_empty() || line.starts_with(':') {
+            return None;
+        }
+        let error: Error = match self.lexer.lex(line) {
+            Ok(tokens) => self.parser.clone().parse(&tokens).err()?.into(),
+            Err(err) => err.into(),
+        };
+        match &error {
+            Error::Syntax(err) if matches!(err.kind(), SyntaxErrorKind::UnexpectedEnd { .. }) => None,
+            Error::Syntax(err)
+                if matches!(err.kind(), SyntaxErrorKind::UnknownSymbol { .. })
+                    && err.span().is_some_and(|span| span.end == line.trim_end().len()) =>
+            {
+                None
+            }
+            _ => Some(format!("  ← {error}")),
+        }
+    }
+
+    // The line with every part coloured according to its style.
+    fn colored(&self, line: &str) -> String {
+        let mut colored = String::with_capacity(line.len() * 2);
+        let mut end = 0;
+        for (span, style) in self.styles(line) {
+            if span.start < end || span.end > line.len() {
+                continue;
+            }
+            colored.push_str(&line[end..span.start]);
+            colored.push_str(style.color());
+            colored.push_str(&line[span.start..span.end]);
+            colored.push_str(RESET);
+            end = span.end;
+        }
+        colored.push_str(&line[end..]);
+        colored
+    }
+}
+
+impl Default for EditorHelper {
+    fn default() -> Self {
+        Self::new(use_colors())
+    }
+}
+
+impl Completer for EditorHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Hinter for EditorHelper {
+    type Hint = String;
+
+    fn hint(&self, line: &str, pos: usize, _: &Context<'_>) -> Option<String> {
+        if pos < line.len() {
+            return None;
+        }
+        self.error_hint(line)
+    }
+}
+
+impl Highlighter for EditorHelper {
+    fn highlight<'l>(&self, line: &'l str, _: usize) -> Cow<'l, str> {
+        if self.colors {
+            Cow::Owned(self.colored(line))
+        } else {
+            Cow::Borrowed(line)
+        }
+    }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        if self.colors {
+            Cow::Owned(format!("{DIM}{hint}{RESET}"))
+        } else {
+            Cow::Borrowed(hint)
+        }
+    }
+
+    // Any character can change the styles of the whole line, e.g. closing a parenthesis
+    fn highlight_char(&self, _: &str, _: usize, kind: CmdKind) -> bool {
+        self.colors && kind != CmdKind::MoveCursor
+    }
+}
+
+impl Validator for EditorHelper {}
+
+impl Helper for EditorHelper {}
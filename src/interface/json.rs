@@ -0,0 +1,117 @@
+use std::fmt;
+use serde::Serialize;
+use crate::errors::diagnostic::{self, Diagnostic, Span};
+use crate::errors::interpreter_error::InterpreterErrorKind;
+use crate::errors::lexer_error::LexerErrorKind;
+use crate::errors::parser_error::SyntaxErrorKind;
+use crate::errors::Error;
+use crate::expressions::interpreter::number::{Notation, Number};
+use crate::expressions::parser::ast::Expression;
+
+/// The outcome of a statement for `--output json`, written as a single line of JSON:
+///
+/// ```json
+/// {"input":"x = 2^10","value":"1024","type":"float","variable":"x","error":null}
+/// ```
+///
+/// Every field is always there, `null` when it doesn't apply. Function definitions have no value, and have the
+/// type `function`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    pub input: String,
+    pub value: Option<String>,
+    #[serde(rename = "type")]
+    pub value_type: Option<&'static str>,
+    pub variable: Option<String>,
+    pub error: Option<ErrorRecord>,
+}
+
+/// An error of a [`Record`]. The span is in bytes of the source the statement was read from (the expression of
+/// `-e`, the script, or the line), and the line and column, both starting at 1, are where the span starts.
+///
+/// The kind of error is written with its details next to the other fields:
+///
+/// ```json
+/// {"stage":"interpreter","kind":"cyclic_definition","cycle":["a","b","a"],"code":"E0201",...}
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorRecord {
+    /// `lexer`, `syntax` or `interpreter`.
+    pub stage: &'static str,
+    #[serde(flatten)]
+    pub kind: ErrorKind,
+    pub code: &'static str,
+    pub message: String,
+    pub help: Option<String>,
+    pub span: Option<Span>,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The kind of an [`ErrorRecord`], whichever stage it comes from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ErrorKind {
+    Lexer(LexerErrorKind),
+    Syntax(SyntaxErrorKind),
+    Interpreter(InterpreterErrorKind),
+}
+
+impl Record {
+    /// The record of the statement `input`, before it is evaluated: the variable or function it defines, if any.
+    #[must_use]
+    pub fn new(input: &str, expression: &Expression) -> Self {
+        let (variable, value_type) = match expression {
+            Expression::Assignment(name, _) | Expression::Binding(name, _) => (Some(name.clone()), None),
+            Expression::FunctionDefinition(name, _, _) => (Some(name.clone()), Some("function")),
+            _ => (None, None),
+        };
+        Record { input: input.into(), value: None, value_type, variable, error: None }
+    }
+
+    /// Sets the value the statement evaluated to. Function definitions keep having none.
+    #[must_use]
+    pub fn with_value(self, value: &Number, notation: Notation) -> Self {
+        if self.value_type.is_some() {
+            return self;
+        }
+        Record { value: Some(value.display(notation)), value_type: Some(value.type_name()), ..self }
+    }
+
+    /// Sets the error the statement failed with, whose span is in `source`. The variable or function it defines is
+    /// kept, but it has no type as it has no value.
+    #[must_use]
+    pub fn with_error(self, error: &Error, source: &str) -> Self {
+        let span = error.span();
+        let (line, column) = diagnostic::position(source, span.map_or(0, |span| span.start));
+        let (stage, kind) = match error {
+            Error::Lexer(err) => ("lexer", ErrorKind::Lexer(err.kind().clone())),
+            Error::Syntax(err) => ("syntax", ErrorKind::Syntax(err.kind().clone())),
+            Error::Interpreter(err) => ("interpreter", ErrorKind::Interpreter(err.kind().clone())),
+        };
+        let error = ErrorRecord {
+            stage,
+            kind,
+            code: error.code(),
+            message: error.to_string(),
+            help: error.help().map(String::from),
+            span,
+            line,
+            column,
+        };
+        Record { value: None, value_type: None, error: Some(error), ..self }
+    }
+
+    /// The record of the statement `input` failing with `error`, whose span is in `source`, when it isn't known
+    /// what the statement defines.
+    #[must_use]
+    pub fn failed(input: &str, error: &Error, source: &str) -> Self {
+        Record::new(input, &Expression::Eof).with_error(error, source)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).map_err(|_| fmt::Error)?)
+    }
+}
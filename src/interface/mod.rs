@@ -1,2 +1,6 @@
 pub mod cli;
-pub mod repl;
\ No newline at end of file
+pub mod editor;
+pub mod json;
+pub mod repl;
+pub mod runner;
+pub mod script;
\ No newline at end of file
@@ -1,12 +1,161 @@
+use std::fs;
 use std::io;
-use std::io::Write;
-use crate::expressions::interpreter::Interpreter;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use rustyline::error::ReadlineError;
+use rustyline::history::{DefaultHistory, History};
+use rustyline::{CompletionType, Config, Editor};
+use crate::errors::{diagnostic, Error};
+use crate::expressions::interpreter::number::Notation;
+use crate::expressions::interpreter::{Binding, Interpreter};
 use crate::expressions::lexer::Lexer;
+use crate::expressions::lexer::tokens::Kind;
+use crate::expressions::parser::ast::Expression;
 use crate::expressions::parser::Parser;
+use crate::interface::editor::EditorHelper;
+use crate::interface::repl::commands::Command;
+use crate::interface::runner;
+/// The meta-commands of the REPL, which start with a colon and manage the session rather than compute.
+pub mod commands {
+    pub struct CommandInfo {
+        pub name: &'static str,
+        pub arguments: &'static str,
+        pub description: &'static str,
+    }
+
+    pub const COMMANDS: &[CommandInfo] = &[
+        CommandInfo { name: ":help", arguments: "", description: "show this help" },
+        CommandInfo { name: ":vars", arguments: "", description: "list the variables and functions with their definition and value" },
+        CommandInfo { name: ":del", arguments: "name...", description: "delete variables or functions" },
+        CommandInfo { name: ":clear", arguments: "", description: "delete every variable and function" },
+        CommandInfo { name: ":deps", arguments: "name", description: "show what a variable or function is defined in terms of, as a tree" },
+        CommandInfo { name: ":dependents", arguments: "name", description: "show what is defined in terms of a variable or function, as a tree" },
+        CommandInfo { name: ":dot", arguments: "[file]", description: "export the dependency graph in the Graphviz DOT format" },
+        CommandInfo { name: ":ast", arguments: "expression", description: "show the syntax tree of an expression without evaluating it" },
+        CommandInfo { name: ":tokens", arguments: "expression", description: "show the tokens of an expression without evaluating it" },
+    ];
+
+    #[derive(Debug, PartialEq)]
+    pub enum Command<'a> {
+        Help,
+        Vars,
+        Del(Vec<&'a str>),
+        Clear,
+        Deps(&'a str),
+        Dependents(&'a str),
+        Dot(Option<&'a str>),
+        Ast(&'a str),
+        Tokens(&'a str),
+    }
+
+    impl<'a> Command<'a> {
+        /// Parses a line starting with a colon.
+        ///
+        /// # Errors
+        ///
+        /// Will return an error if the command is unknown or misses its arguments.
+        pub fn parse(line: &'a str) -> Result<Self, String> {
+            let (name, arguments) = line.trim().split_once(char::is_whitespace).unwrap_or((line.trim(), ""));
+            let arguments = arguments.trim();
+            let usage = |command: &str| {
+                let info = COMMANDS.iter().find(|info| info.name == command).map_or("", |info| info.arguments);
+                Err(format!("usage: {command} {info}"))
+            };
+            match name {
+                ":help" => Ok(Command::Help),
+                ":vars" => Ok(Command::Vars),
+                ":del" | ":ast" | ":tokens" if arguments.is_empty() => usage(name),
+                ":del" => Ok(Command::Del(arguments.split_whitespace().collect())),
+                ":clear" => Ok(Command::Clear),
+                ":deps" | ":dependents" if arguments.is_empty() || arguments.contains(char::is_whitespace) => usage(name),
+                ":deps" => Ok(Command::Deps(arguments)),
+                ":dependents" => Ok(Command::Dependents(arguments)),
+                ":dot" if arguments.contains(char::is_whitespace) => usage(name),
+                ":dot" => Ok(Command::Dot(Some(arguments).filter(|file| !file.is_empty()))),
+                ":ast" => Ok(Command::Ast(arguments)),
+                ":tokens" => Ok(Command::Tokens(arguments)),
+                _ => Err(format!("unknown command {name}, see :help")),
+            }
+        }
+    }
+}
+
+const PROMPT: &str = "> ";
+// Prompt of the lines continuing an input whose parentheses are still open
+const CONTINUATION_PROMPT: &str = "... ";
+// Number of lines kept in the history file
+const HISTORY_SIZE: usize = 1000;
+
+// Writes `name` and, below it, the names `next` leads to, recursively, as a tree. `path` holds the names from the
+// root, so that cycles, such as the ones of recursive functions, are only written once.
+fn write_tree<'a>(
+    output: &mut impl Write,
+    name: &'a str,
+    next: &impl Fn(&str) -> Vec<&'a str>,
+    path: &mut Vec<&'a str>,
+    indent: &str,
+) -> io::Result<()> {
+    if path.contains(&name) {
+        return writeln!(output, "{name} (cycle)");
+    }
+    writeln!(output, "{name}")?;
+    path.push(name);
+    let children = next(name);
+    for (index, child) in children.iter().enumerate() {
+        let last = index + 1 == children.len();
+        write!(output, "{indent}{}", if last { "└── " } else { "├── " })?;
+        write_tree(output, child, next, path, &format!("{indent}{}", if last { "    " } else { "│   " }))?;
+    }
+    path.pop();
+    Ok(())
+}
+
+// Writes the syntax tree of `expression` the way `write_tree` writes names, each node being its operator, or what
+// it defines, above its operands.
+fn write_syntax_tree(output: &mut impl Write, expression: &Expression, indent: &str) -> io::Result<()> {
+    let (label, children) = syntax_node(expression);
+    writeln!(output, "{label}")?;
+    for (index, child) in children.iter().enumerate() {
+        let last = index + 1 == children.len();
+        write!(output, "{indent}{}", if last { "└── " } else { "├── " })?;
+        write_syntax_tree(output, child, &format!("{indent}{}", if last { "    " } else { "│   " }))?;
+    }
+    Ok(())
+}
+
+fn syntax_node(expression: &Expression) -> (String, Vec<&Expression>) {
+    match expression {
+        Expression::Program(statements) => {
+            (String::from(";"), statements.iter().map(|statement| &statement.expression).collect())
+        }
+        Expression::Assignment(name, expr) => (format!("{name} ="), vec![expr]),
+        Expression::Binding(name, expr) => (format!("{name} :="), vec![expr]),
+        Expression::FunctionDefinition(name, parameters, body) => {
+            (format!("{name}({}) =", parameters.join(", ")), vec![body])
+        }
+        Expression::Addition(left, right) => (String::from("+"), vec![left, right]),
+        Expression::Subtraction(left, right) => (String::from("-"), vec![left, right]),
+        Expression::Multiplication(left, right) => (String::from("*"), vec![left, right]),
+        Expression::Division(left, right, _) => (String::from("/"), vec![left, right]),
+        Expression::Power(base, exponent, _) => (String::from("^"), vec![base, exponent]),
+        Expression::UnaryPlus(expr) => (String::from("+"), vec![expr]),
+        Expression::UnaryMinus(expr) => (String::from("-"), vec![expr]),
+        Expression::ParenthesisExpression(expr) => (String::from("()"), vec![expr]),
+        Expression::Call(name, arguments, _) => (format!("{name}()"), arguments.iter().collect()),
+        Expression::Quantity(expr, unit) => (unit.to_string(), vec![expr]),
+        Expression::Conversion(expr, unit) => (format!("to {unit}"), vec![expr]),
+        Expression::Literal(numeral) => (numeral.to_string(), Vec::new()),
+        Expression::Variable(name, _) => (name.clone(), Vec::new()),
+        Expression::Eof => (String::new(), Vec::new()),
+    }
+}
+
 pub struct Repl {
     lexer: Lexer,
     parser: Parser,
     interpreter: Interpreter,
+    notation: Notation,
+    json: bool,
 }
 
 impl Repl {
@@ -16,31 +165,280 @@ impl Repl {
             lexer,
             parser,
             interpreter,
+            notation: Notation::default(),
+            json: false,
         }
     }
 
-    fn looper(&mut self) -> Result<(), String> {
-        let mut line_buffer = String::new();
-        print!("> ");
-        io::stdout().flush().map_err(|err| err.to_string())?;
-        io::stdin().read_line(&mut line_buffer).map_err(|e| e.to_string())?;
-        if line_buffer.is_empty() {
-            Ok(())
+    /// Sets how results are displayed.
+    pub fn set_notation(&mut self, notation: Notation) {
+        self.notation = notation;
+    }
+
+    /// Sets whether the session is line-delimited JSON for other programs: no prompts nor greeting, each statement
+    /// of a line is written as a JSON [`Record`](crate::interface::json::Record), and so are the errors, on the output. Commands aren't available,
+    /// and empty lines are skipped rather than ending the session.
+    pub fn set_json(&mut self, json: bool) {
+        self.json = json;
+    }
+
+    /// Evaluates the lines read from `input` until an empty line or the end of the input. A line can hold several
+    /// statements separated by `;`, and an input whose parentheses are still open continues on the next lines.
+    /// Results are written to `output` and errors to `errors`, a failing statement ending its line but keeping what
+    /// was defined before it, see [`runner::run`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if reading the input or writing the output fails.
+    pub fn session(&mut self, input: impl BufRead, output: &mut impl Write, errors: &mut impl Write) -> io::Result<()> {
+        let mut lines = input.lines();
+        let mut pending = String::new();
+        loop {
+            if !self.json {
+                write!(output, "{}", if pending.is_empty() { PROMPT } else { CONTINUATION_PROMPT })?;
+            }
+            output.flush()?;
+            let Some(line_buffer) = lines.next() else {
+                // An unfinished input is still reported
+                self.process(&pending, output, errors)?;
+                return Ok(());
+            };
+            if let Some(input) = self.complete_input(&mut pending, line_buffer?.trim_end()) {
+                if !self.process(&input, output, errors)? {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    // Adds a line to the pending input, returning the whole input once it is complete.
+    fn complete_input(&self, pending: &mut String, line: &str) -> Option<String> {
+        if !pending.is_empty() {
+            pending.push('\n');
+        }
+        pending.push_str(line);
+        if self.is_incomplete(pending) {
+            None
         } else {
-            let tokens = self.lexer.lex(&line_buffer).map_err(|err| format!("Lexer error: {err}"))?;
-            let ast = self.parser.parse(&tokens).map_err(|err| format!("Parser error: {err}"))?;
-            let result = self.interpreter.interpret(ast).map_err(|err| format!("Interpreter error: {err}"))?;
-            println!("= {result}");
-            self.looper()
+            Some(std::mem::take(pending))
         }
     }
+
+    // Whether the input has parentheses still open, and so continues on the next line.
+    fn is_incomplete(&self, input: &str) -> bool {
+        if input.starts_with(':') && !self.json {
+            return false;
+        }
+        self.lexer.lex(input).is_ok_and(|tokens| {
+            let count = |parenthesis: &str| {
+                tokens.iter().filter(|token| token.kind == Kind::Separator && token.raw_value == parenthesis).count()
+            };
+            count("(") > count(")")
+        })
+    }
+
+    // Evaluates a line of a session and writes its outcome, returning whether the session goes on.
+    fn process(&mut self, line: &str, output: &mut impl Write, errors: &mut impl Write) -> io::Result<bool> {
+        if line.is_empty() {
+            return Ok(self.json);
+        }
+        if line.starts_with(':') && !self.json {
+            match Command::parse(line) {
+                Ok(command) => self.execute(&command, output, errors)?,
+                Err(err) => writeln!(errors, "error: {err}")?,
+            }
+            return Ok(true);
+        }
+        for outcome in runner::run(line, (&self.lexer, &mut self.parser, &mut self.interpreter), false) {
+            match (outcome.result, outcome.definition) {
+                (Ok(value), _) if self.json => writeln!(output, "{}", outcome.record.with_value(&value, self.notation))?,
+                (Ok(_), Some(signature)) => writeln!(output, "{signature} defined")?,
+                (Ok(value), None) => writeln!(output, "= {}", value.display(self.notation))?,
+                (Err(err), _) if self.json => writeln!(output, "{}", outcome.record.with_error(&err, line))?,
+                (Err(err), _) => writeln!(errors, "{}", diagnostic::render(line, &err))?,
+            }
+        }
+        Ok(true)
+    }
+
+    fn execute(&mut self, command: &Command, output: &mut impl Write, errors: &mut impl Write) -> io::Result<()> {
+        match command {
+            Command::Help => {
+                for info in commands::COMMANDS {
+                    let usage = format!("{} {}", info.name, info.arguments);
+                    writeln!(output, "{usage:<20} {}", info.description)?;
+                }
+            }
+            Command::Vars => self.list_definitions(output)?,
+            Command::Del(names) => {
+                for name in names {
+                    if self.interpreter.remove(name) {
+                        self.parser.forget(name);
+                        writeln!(output, "{name} deleted")?;
+                    } else {
+                        writeln!(errors, "error: {name} is not defined")?;
+                    }
+                }
+            }
+            Command::Clear => {
+                self.interpreter.clear();
+                self.parser.clear();
+                writeln!(output, "Every variable and function deleted")?;
+            }
+            Command::Deps(name) | Command::Dependents(name) => {
+                let graph = self.interpreter.dependency_graph();
+                if !graph.names().contains(name) {
+                    return writeln!(errors, "error: {name} is not defined");
+                }
+                let next = |name: &str| match command {
+                    Command::Deps(_) => graph.dependencies(name),
+                    _ => graph.dependents(name),
+                };
+                write_tree(output, name, &next, &mut Vec::new(), "")?;
+            }
+            Command::Dot(file) => {
+                let dot = self.interpreter.dependency_dot();
+                match file {
+                    None => writeln!(output, "{dot}")?,
+                    Some(file) => match fs::write(file, dot + "\n") {
+                        Ok(()) => writeln!(output, "Dependency graph written to {file}")?,
+                        Err(err) => writeln!(errors, "error: cannot write {file}: {err}")?,
+                    },
+                }
+            }
+            Command::Ast(expression) => match self.parse(expression) {
+                Ok(ast) => write_syntax_tree(output, &ast, "")?,
+                Err(err) => writeln!(errors, "{}", diagnostic::render(expression, &err))?,
+            },
+            Command::Tokens(expression) => match self.lexer.lex(expression) {
+                Ok(tokens) => {
+                    for token in tokens {
+                        let span = token.span();
+                        writeln!(output, "{:<10} {:<10} {}..{}", format!("{:?}", token.kind), token.raw_value, span.start, span.end)?;
+                    }
+                }
+                Err(err) => writeln!(errors, "{}", diagnostic::render(expression, &err))?,
+            },
+        }
+        Ok(())
+    }
+
+    // Writes every variable with its definition and current value, or its frozen value, then every function.
+    fn list_definitions(&mut self, output: &mut impl Write) -> io::Result<()> {
+        let variables: Vec<(String, Binding)> = self
+            .interpreter
+            .variables()
+            .into_iter()
+            .map(|(name, binding)| (name.to_string(), binding.clone()))
+            .collect();
+        let functions: Vec<String> = self
+            .interpreter
+            .functions()
+            .into_iter()
+            .map(|(name, parameters, body)| format!("{name}({}) = {body}", parameters.join(", ")))
+            .collect();
+        if variables.is_empty() && functions.is_empty() {
+            return writeln!(output, "Nothing defined yet");
+        }
+        for (name, binding) in variables {
+            let expr = match binding {
+                Binding::Lazy(expr) => expr,
+                Binding::Frozen(value) => {
+                    writeln!(output, "{name} := {} (frozen)", value.display(self.notation))?;
+                    continue;
+                }
+            };
+            // Evaluating a variable has no side effect, the session is left as it was
+            match self.interpreter.value(&name) {
+                Ok(value) => writeln!(output, "{name} = {expr} = {}", value.display(self.notation))?,
+                Err(err) => writeln!(output, "{name} = {expr} (error: {err})")?,
+            }
+        }
+        for function in functions {
+            writeln!(output, "{function}")?;
+        }
+        Ok(())
+    }
+
+    // Parses an expression without defining anything it assigns.
+    fn parse(&self, expression: &str) -> Result<Expression, Error> {
+        let tokens = self.lexer.lex(expression)?;
+        Ok(self.parser.clone().parse(&tokens)?)
+    }
+
+    /// Where the lines typed in interactive sessions are remembered, `ralculator/history` in the user's config
+    /// directory, if there is one.
+    #[must_use]
+    pub fn history_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|directory| directory.join("ralculator").join("history"))
+    }
+
+    // An interactive session on a terminal, with line editing and the history of the previous sessions.
+    // Ctrl-C cancels the line being typed while Ctrl-D, like an empty line, ends the session.
+    fn edit(&mut self) -> Result<(), ReadlineError> {
+        let config = Config::builder()
+            .max_history_size(HISTORY_SIZE)?
+            .completion_type(CompletionType::List)
+            .build();
+        let mut editor: Editor<EditorHelper, DefaultHistory> = Editor::with_config(config)?;
+        editor.set_helper(Some(EditorHelper::default()));
+        let history_path = Self::history_path();
+        if let Some(path) = &history_path {
+            // There is no history before the first session
+            let _ = editor.load_history(path);
+        }
+        let (mut output, mut errors) = (io::stdout(), io::stderr());
+        let mut pending = String::new();
+        loop {
+            if let Some(helper) = editor.helper_mut() {
+                helper.update(&self.parser, &self.interpreter);
+            }
+            let prompt = if pending.is_empty() { PROMPT } else { CONTINUATION_PROMPT };
+            let line_buffer = match editor.readline(prompt) {
+                Ok(line_buffer) => line_buffer,
+                Err(ReadlineError::Interrupted) => {
+                    pending.clear();
+                    continue;
+                }
+                Err(ReadlineError::Eof) => break,
+                Err(err) => return Err(err),
+            };
+            let Some(input) = self.complete_input(&mut pending, line_buffer.trim_end()) else {
+                continue;
+            };
+            if !input.is_empty() {
+                editor.add_history_entry(input.as_str())?;
+            }
+            if !self.process(&input, &mut output, &mut errors)? {
+                break;
+            }
+        }
+        if let Some(path) = history_path.filter(|_| !editor.history().is_empty()) {
+            if let Some(directory) = path.parent() {
+                fs::create_dir_all(directory)?;
+            }
+            editor.save_history(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Runs an interactive session on the standard input and output. On a terminal, lines can be edited and the
+    /// history of the previous sessions is available, see [`Repl::history_path`].
+    ///
     /// # Errors
     ///
-    /// Will return an error if it fails interpreting a line.
-    /// Error could be either `LexerError`, `SyntaxError` (parser error) or `InterpreterError`.
+    /// Will return an error if reading the standard input, writing the standard output or saving the history fails.
+    /// Errors in the lines themselves are reported and the session goes on.
     pub fn run(&mut self) -> Result<(), String> {
-        Repl::greet();
-        self.looper()
+        if !self.json {
+            Repl::greet();
+        }
+        if io::stdin().is_terminal() && !self.json {
+            self.edit().map_err(|err| err.to_string())
+        } else {
+            self.session(io::stdin().lock(), &mut io::stdout(), &mut io::stderr())
+                .map_err(|err| err.to_string())
+        }
     }
 
     fn tabs(n: usize) -> String {
@@ -50,12 +448,22 @@ impl Repl {
     fn greet() {
         println!("=== Interactive mathematical expression calculator ===");
         println!("{}Usage:", Self::tabs(1));
-        println!("{}- Supported operator: +, -, *, /.", Self::tabs(2));
+        println!("{}- Supported operator: +, -, *, /, ^.", Self::tabs(2));
         println!(
             "{}- Supports assigning expressions to variables.",
             Self::tabs(2)
         );
         println!("{}- Supports parenthesis expression.", Self::tabs(2));
-        println!("{}Press enter on an empty line to exit!", Self::tabs(1));
+        println!(
+            "{}- Supports math functions such as sqrt(x), log(base, x) or max(x, ...).",
+            Self::tabs(2)
+        );
+        println!("{}- Supports defining functions, e.g. f(x, y) = x^2 + y.", Self::tabs(2));
+        println!("{}- Knows the constants pi, e, tau, phi, inf, nan and i.", Self::tabs(2));
+        println!("{}- Supports complex numbers such as 3+4i, with re(z), im(z), conj(z) and arg(z).", Self::tabs(2));
+        println!("{}- Supports units and conversions, e.g. 3 km + 200 m or 60 mph to m/s.", Self::tabs(2));
+        println!("{}Type :help for the commands managing the session, such as :vars.", Self::tabs(1));
+        println!("{}Use the arrow keys to edit the line or go through the history, Ctrl-R to search it, tab to complete names.", Self::tabs(1));
+        println!("{}Ctrl-C cancels the line, Ctrl-D or enter on an empty line exits!", Self::tabs(1));
     }
 }
@@ -0,0 +1,88 @@
+use crate::errors::diagnostic::{Diagnostic, Span};
+use crate::errors::Error;
+use crate::expressions::interpreter::number::Number;
+use crate::expressions::interpreter::Interpreter;
+use crate::expressions::lexer::tokens::{Kind, Token};
+use crate::expressions::lexer::Lexer;
+use crate::expressions::parser::ast::Expression;
+use crate::expressions::parser::Parser;
+use crate::interface::json::Record;
+
+/// A statement run by [`run`], with its outcome.
+#[derive(Debug)]
+pub struct Outcome<'a> {
+    /// The statement as written in the source, or the whole source if it couldn't be lexed.
+    pub input: &'a str,
+    /// The record of the statement before it was evaluated, see [`Record::new`], with only its input if it couldn't
+    /// be parsed.
+    pub record: Record,
+    /// The signature of the function the statement defines, such as `f(x, y)`.
+    pub definition: Option<String>,
+    /// The value of the statement, or its error, whose span is in the source.
+    pub result: Result<Number, Error>,
+}
+
+/// Runs the statements of `source`, separated by `;` or newlines, one after the other: each statement is parsed
+/// once the previous one has been evaluated, so that it can use what they define. A failing statement doesn't undo
+/// the previous ones, and the statements after it are only run when `keep_going` is set. The whole source fails if
+/// it can't be lexed.
+#[must_use]
+pub fn run<'a>(
+    source: &'a str,
+    (lexer, parser, interpreter): (&Lexer, &mut Parser, &mut Interpreter),
+    keep_going: bool,
+) -> Vec<Outcome<'a>> {
+    let tokens = match lexer.lex(source) {
+        Ok(tokens) => tokens,
+        Err(err) => {
+            let input = source.trim();
+            let record = Record::new(input, &Expression::Eof);
+            return vec![Outcome { input, record, definition: None, result: Err(err.into()) }];
+        }
+    };
+    let mut outcomes = Vec::new();
+    for tokens in tokens.split(|token| token.kind == Kind::Terminator).filter(|tokens| !tokens.is_empty()) {
+        let outcome = run_statement(source, tokens, parser, interpreter);
+        let failed = outcome.result.is_err();
+        outcomes.push(outcome);
+        if failed && !keep_going {
+            break;
+        }
+    }
+    outcomes
+}
+
+// A statement goes from its first token to its last one, leaving the comments out. Errors without a span are about
+// the whole statement.
+fn run_statement<'a>(source: &'a str, tokens: &[Token], parser: &mut Parser, interpreter: &mut Interpreter) -> Outcome<'a> {
+    let span = Span::new(tokens[0].span().start, tokens[tokens.len() - 1].span().end);
+    let input = &source[span.start..span.end];
+    let expression = match parser.parse(tokens) {
+        Ok(expression) => expression,
+        Err(err) => {
+            let record = unparsed_record(input, tokens);
+            return Outcome { input, record, definition: None, result: Err(err.into()) };
+        }
+    };
+    let record = Record::new(input, &expression);
+    let definition = match &expression {
+        Expression::FunctionDefinition(name, parameters, _) => Some(format!("{name}({})", parameters.join(", "))),
+        _ => None,
+    };
+    let result = interpreter
+        .interpret(expression)
+        .map_err(|err| if err.span().is_some() { err } else { err.with_span(span) }.into());
+    Outcome { input, record, definition, result }
+}
+
+// The record of a statement that can't be parsed, which is still an assignment if it starts like one: `x = 1 +`
+// assigns x.
+fn unparsed_record(input: &str, tokens: &[Token]) -> Record {
+    let mut record = Record::new(input, &Expression::Eof);
+    if let [name, operator, ..] = tokens {
+        if name.kind == Kind::Identifier && operator.kind == Kind::Operator && matches!(operator.raw_value.as_str(), "=" | ":=") {
+            record.variable = Some(name.raw_value.clone());
+        }
+    }
+    record
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+use std::io;
+use std::io::Write;
+use crate::errors::diagnostic::{self, Diagnostic};
+use crate::errors::Error;
+use crate::expressions::interpreter::number::Notation;
+use crate::expressions::interpreter::Interpreter;
+use crate::expressions::lexer::tokens::Kind;
+use crate::expressions::lexer::Lexer;
+use crate::expressions::parser::Parser;
+use crate::interface::runner;
+
+/// A statement of a script that failed, with where it is in the script.
+#[derive(Debug)]
+pub struct Failure {
+    pub origin: String,
+    pub line: usize,
+    pub column: usize,
+    pub error: Error,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}: {}", self.origin, self.line, self.column, self.error)
+    }
+}
+
+/// Runs scripts: statements separated by newlines or `;`, with `#` comments. The results of the bare expressions
+/// are printed, assignments and function definitions are not.
+pub struct Script {
+    lexer: Lexer,
+    parser: Parser,
+    interpreter: Interpreter,
+    notation: Notation,
+    keep_going: bool,
+    json: bool,
+}
+
+impl Script {
+    #[must_use]
+    pub fn new(lexer: Lexer, parser: Parser, interpreter: Interpreter) -> Self {
+        Self {
+            lexer,
+            parser,
+            interpreter,
+            notation: Notation::default(),
+            keep_going: false,
+            json: false,
+        }
+    }
+
+    /// Sets how results are displayed.
+    pub fn set_notation(&mut self, notation: Notation) {
+        self.notation = notation;
+    }
+
+    /// Sets whether the script goes on after a failing statement, instead of stopping there.
+    pub fn set_keep_going(&mut self, keep_going: bool) {
+        self.keep_going = keep_going;
+    }
+
+    /// Sets whether each statement, assignments and definitions included, is written as a JSON [`Record`](crate::interface::json::Record), its
+    /// errors included, instead of as text.
+    pub fn set_json(&mut self, json: bool) {
+        self.json = json;
+    }
+
+    /// Runs the source, read from `origin`, writing the results to `output` and the errors to `errors`.
+    /// When going on after failures, they are summed up at the end. In JSON, everything is written to `output`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if writing the output fails. The failures of the script itself are returned, the
+    /// first one only unless it keeps going.
+    pub fn run(
+        &mut self,
+        origin: &str,
+        source: &str,
+        output: &mut impl Write,
+        errors: &mut impl Write,
+    ) -> io::Result<Vec<Failure>> {
+        let mut failures = Vec::new();
+        for (offset, chunk) in self.chunks(source) {
+            let session = (&self.lexer, &mut self.parser, &mut self.interpreter);
+            for outcome in runner::run(chunk, session, self.keep_going) {
+                match outcome.result {
+                    Ok(value) if self.json => writeln!(output, "{}", outcome.record.with_value(&value, self.notation))?,
+                    // Assignments and function definitions print nothing
+                    Ok(value) if outcome.record.variable.is_none() => writeln!(output, "{}", value.display(self.notation))?,
+                    Ok(_) => {}
+                    Err(error) => {
+                        let error = error.offset(offset);
+                        if self.json {
+                            writeln!(output, "{}", outcome.record.with_error(&error, source))?;
+                        } else {
+                            writeln!(errors, "{}", diagnostic::render_in(origin, source, &error))?;
+                        }
+                        let (line, column) = diagnostic::position(source, error.span().map_or(offset, |span| span.start));
+                        failures.push(Failure { origin: origin.into(), line, column, error });
+                        if !self.keep_going {
+                            return Ok(failures);
+                        }
+                    }
+                }
+            }
+        }
+        if failures.len() > 1 && !self.json {
+            writeln!(errors, "error: {} statements failed:", failures.len())?;
+            for failure in &failures {
+                writeln!(errors, "  {failure}")?;
+            }
+        }
+        Ok(failures)
+    }
+
+    // Splits the source in parts that can be lexed and parsed on their own, with their offset in the source:
+    // lines, or several lines when parentheses are still open at the end of a line.
+    fn chunks<'a>(&self, source: &'a str) -> Vec<(usize, &'a str)> {
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        for (index, _) in source.match_indices('\n').chain(std::iter::once((source.len(), ""))) {
+            let chunk = &source[start..index];
+            if !self.is_incomplete(chunk) || index == source.len() {
+                chunks.push((start, chunk));
+                start = (index + 1).min(source.len());
+            }
+        }
+        chunks
+    }
+
+    fn is_incomplete(&self, chunk: &str) -> bool {
+        self.lexer.lex(chunk).is_ok_and(|tokens| {
+            let count = |parenthesis: &str| {
+                tokens.iter().filter(|token| token.kind == Kind::Separator && token.raw_value == parenthesis).count()
+            };
+            count("(") > count(")")
+        })
+    }
+}
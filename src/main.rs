@@ -1,39 +1,112 @@
 use ralculator::expressions::lexer::Lexer;
 use clap::Parser;
+use ralculator::errors::diagnostic;
+use ralculator::expressions::interpreter::number::Notation;
 use ralculator::expressions::interpreter::Interpreter;
-use ralculator::interface::cli::{Cli, Mode};
+use ralculator::interface::cli::{self, Cli, Command, Output};
 use ralculator::interface::repl::Repl;
+use ralculator::interface::runner;
+use ralculator::interface::script::Script;
+use std::io::Read;
+use std::process::ExitCode;
 
-fn program() -> Result<(), String> {
+// Runs the script in `file`, or in the standard input for -, exiting with the status of its first failure
+fn run_script(file: &str, script: &mut Script) -> Result<ExitCode, String> {
+    let source = if file == "-" {
+        let mut source = String::new();
+        std::io::stdin().read_to_string(&mut source).map(|_| source)
+    } else {
+        std::fs::read_to_string(file)
+    }
+    .map_err(|err| format!("error: cannot read {file}: {err}"))?;
+    let origin = if file == "-" { "<stdin>" } else { file };
+    let failures = script
+        .run(origin, &source, &mut std::io::stdout(), &mut std::io::stderr())
+        .map_err(|err| err.to_string())?;
+    Ok(failures.first().map_or(ExitCode::SUCCESS, |failure| ExitCode::from(cli::exit_code(&failure.error))))
+}
+
+// How the statements of `-e` are printed
+#[derive(Clone, Copy)]
+enum Printing {
+    WithExpressions,
+    Quiet,
+    Json,
+}
+
+// Evaluates the statements of `raw_expr`, printing each of them with its result, only the results when quiet, or
+// their records in JSON. The first failing statement is reported, and gives the exit status.
+fn evaluate(
+    raw_expr: &str,
+    session: (&Lexer, &mut ralculator::expressions::parser::Parser, &mut Interpreter),
+    notation: Notation,
+    printing: Printing,
+) -> ExitCode {
+    for outcome in runner::run(raw_expr, session, false) {
+        let value = match outcome.result {
+            Ok(value) => value,
+            Err(err) => {
+                match printing {
+                    Printing::Json => println!("{}", outcome.record.with_error(&err, raw_expr)),
+                    _ => eprintln!("{}", diagnostic::render(raw_expr, &err)),
+                }
+                return ExitCode::from(cli::exit_code(&err));
+            }
+        };
+        match (printing, outcome.definition) {
+            (Printing::Json, _) => println!("{}", outcome.record.with_value(&value, notation)),
+            (Printing::Quiet, Some(_)) => {}
+            (Printing::Quiet, None) => println!("{}", value.display(notation)),
+            (Printing::WithExpressions, Some(_)) => println!("{}", outcome.input),
+            (Printing::WithExpressions, None) => println!("{} = {}", outcome.input, value.display(notation)),
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn program() -> Result<ExitCode, String> {
     let lexer = Lexer::new();
     let mut parser = ralculator::expressions::parser::Parser::new();
-    let mut interpreter = Interpreter::new();
     let cli = Cli::parse();
-    match cli.mode {
-        Mode {
-            interactive: true,
-            exec: None,
-        } => {
-            let mut repl = Repl::new(lexer, parser, interpreter);
-            repl.run()
-        }
-        Mode {
-            interactive: false,
-            exec: Some(raw_expr),
-        } => {
-            let tokens = lexer.lex(&raw_expr).map_err(|err| format!("Lexer error: {err}"))?;
-            let ast = parser.parse(&tokens).map_err(|err| format!("Parser error: {err}"))?;
-            let result = interpreter.interpret(ast).map_err(|err| format!("Interpreter error: {err}"))?;
-            println!("{raw_expr} = {result}");
-            Ok(())
+    let mut interpreter = Interpreter::with_backend(cli.backend()?);
+    let notation = cli.format.notation();
+    let file = match (&cli.command, &cli.mode.script) {
+        (Some(Command::Run { file }), _) | (None, Some(file)) => Some(file),
+        (None, None) => None,
+    };
+    if let Some(file) = file {
+        let mut script = Script::new(lexer, parser, interpreter);
+        script.set_notation(notation);
+        script.set_keep_going(cli.keep_going);
+        script.set_json(cli.format.output == Output::Json);
+        return run_script(file, &mut script);
+    }
+    // clap requires exactly one mode, and scripts were run above, so without an expression the session is interactive
+    if let Some(raw_expr) = cli.mode.exec {
+        let printing = match (cli.format.output, cli.format.quiet) {
+            (Output::Json, _) => Printing::Json,
+            (Output::Text, true) => Printing::Quiet,
+            (Output::Text, false) => Printing::WithExpressions,
+        };
+        Ok(evaluate(&raw_expr, (&lexer, &mut parser, &mut interpreter), notation, printing))
+    } else {
+        let mut repl = Repl::new(lexer, parser, interpreter);
+        repl.set_notation(notation);
+        repl.set_json(cli.format.output == Output::Json);
+        repl.run()?;
+        if cli.format.output == Output::Text {
+            println!("Goodbye.");
         }
-        _ => Err(String::from("wtf")),
+        Ok(ExitCode::SUCCESS)
     }
 }
 
-fn main() {
+fn main() -> ExitCode {
     match program() {
-        Ok(()) => println!("Goodbye."),
-        Err(e) => eprintln!("{e}"),
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(cli::EXIT_FAILURE)
+        }
     }
 }
@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use ralculator::errors::Error;
+    use ralculator::expressions::interpreter::Interpreter;
+    use ralculator::expressions::lexer::Lexer;
+    use ralculator::interface::cli::{self, Cli, Command};
+
+    // Evaluates the line, returning the error it fails with.
+    fn fail(line: &str) -> Error {
+        let mut parser = ralculator::expressions::parser::Parser::new();
+        let mut evaluate = || -> Result<(), Error> {
+            let expression = parser.parse(&Lexer::new().lex(line)?)?;
+            Interpreter::new().interpret(expression)?;
+            Ok(())
+        };
+        evaluate().unwrap_err()
+    }
+
+    #[test]
+    fn each_kind_of_error_has_its_exit_code() {
+        assert_eq!(cli::exit_code(&fail("1 $ 2")), cli::EXIT_LEXER_ERROR);
+        assert_eq!(cli::exit_code(&fail("1 +")), cli::EXIT_SYNTAX_ERROR);
+        assert_eq!(cli::exit_code(&fail("1 / 0")), cli::EXIT_INTERPRETER_ERROR);
+        let codes = [cli::EXIT_FAILURE, 2, cli::EXIT_LEXER_ERROR, cli::EXIT_SYNTAX_ERROR, cli::EXIT_INTERPRETER_ERROR];
+        assert!(codes.iter().enumerate().all(|(i, code)| *code != 0 && !codes[..i].contains(code)));
+    }
+
+    #[test]
+    fn quiet_has_a_raw_alias() {
+        assert!(!Cli::try_parse_from(["ralculator", "-e", "1"]).unwrap().format.quiet);
+        assert!(Cli::try_parse_from(["ralculator", "-q", "-e", "1"]).unwrap().format.quiet);
+        assert!(Cli::try_parse_from(["ralculator", "--quiet", "-e", "1"]).unwrap().format.quiet);
+        assert!(Cli::try_parse_from(["ralculator", "--raw", "-e", "1"]).unwrap().format.quiet);
+    }
+
+    #[test]
+    fn scripts_are_run_with_the_run_subcommand_or_directly() {
+        let cli = Cli::try_parse_from(["ralculator", "run", "budget.calc", "-k"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Run { file }) if file == "budget.calc"));
+        assert!(cli.keep_going);
+        let cli = Cli::try_parse_from(["ralculator", "-"]).unwrap();
+        assert_eq!(cli.mode.script.as_deref(), Some("-"));
+        assert!(Cli::try_parse_from(["ralculator"]).is_err());
+        assert!(Cli::try_parse_from(["ralculator", "-e", "1", "budget.calc"]).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use ralculator::errors::diagnostic::{render, Diagnostic, Span};
+    use ralculator::errors::interpreter_error::{InterpreterError, InterpreterErrorKind};
+    use ralculator::errors::parser_error::SyntaxErrorKind;
+    use ralculator::errors::Error;
+    use ralculator::expressions::interpreter::Interpreter;
+    use ralculator::expressions::lexer::Lexer;
+    use ralculator::expressions::parser::Parser;
+
+    #[test]
+    fn syntax_errors_point_at_the_faulty_token() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let err = parser.parse(&lexer.lex("1 + y * 2").unwrap()).unwrap_err();
+        assert_eq!(err.kind(), &SyntaxErrorKind::UnknownSymbol { name: "y".into() });
+        assert_eq!(err.span(), Some(Span::new(4, 5)));
+        assert_eq!(
+            render("1 + y * 2", &err),
+            "error[E0103]: Couldn't find symbol y\n \
+             --> 1:5\n  \
+             |\n\
+             1 | 1 + y * 2\n  \
+             |     ^\n  \
+             = help: define it first, e.g. y = 1"
+        );
+    }
+
+    #[test]
+    fn missing_tokens_point_at_the_end_of_the_line() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let err = parser.parse(&lexer.lex("  max(1, 2").unwrap()).unwrap_err();
+        assert_eq!(err.kind(), &SyntaxErrorKind::UnexpectedEnd { expected: "')'".into() });
+        assert_eq!(err.span(), Some(Span::new(10, 10)));
+    }
+
+    #[test]
+    fn interpreter_errors_point_at_what_failed() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        let mut eval = |line: &str| eval(line).unwrap_err();
+        let err = eval("1 + 2/(2 - 2)");
+        assert_eq!(err.kind(), &InterpreterErrorKind::DivisionByZero);
+        assert_eq!(err.span(), Some(Span::new(4, 13)));
+        assert!(render("1 + 2/(2 - 2)", &err).ends_with("1 | 1 + 2/(2 - 2)\n  |     ^^^^^^^^^"));
+        assert_eq!(eval("2 * sqrt(-1 m)").span(), Some(Span::new(4, 14)));
+        assert_eq!(eval("1 + 0^-1").span(), Some(Span::new(4, 8)));
+        // Errors within a variable or a function are reported where they are used
+        assert_eq!(eval("f(x) = 1 / x; g(x) = f(x - 1); 1 + g(1)").span(), Some(Span::new(35, 39)));
+        assert_eq!(eval("z = 1; y = 1 / z; z = 0; 2 * y").span(), Some(Span::new(29, 30)));
+        // The other ones are about the whole line
+        let err = eval("1 m + 1 s");
+        assert_eq!(err.span(), None);
+        assert!(render(" 1 m + 1 s ", &err).ends_with("1 |  1 m + 1 s \n  |  ^^^^^^^^^"));
+    }
+
+    #[test]
+    fn render_diagnostics_on_any_line() {
+        let first = InterpreterError::new(InterpreterErrorKind::DivisionByZero).with_span(Span::new(0, 3));
+        let second = InterpreterError::new(InterpreterErrorKind::UndefinedFunction { name: "f".into() })
+            .with_span(Span::new(6, 20))
+            .with_help("help");
+        assert_eq!(
+            render("abc\nµ xyz\n", &first),
+            "error[E0203]: Cannot divide by 0.\n --> 1:1\n  |\n1 | abc\n  | ^^^"
+        );
+        assert_eq!(
+            render("abc\nµ xyz\n", &second),
+            "error[E0205]: Function f not found\n --> 2:2\n  |\n2 | µ xyz\n  |  ^^^^\n  = help: help"
+        );
+    }
+
+    #[test]
+    fn every_error_converts_to_the_top_level_error() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| -> Result<f64, Error> {
+            let ast = parser.parse(&lexer.lex(line)?)?;
+            Ok(interpreter.interpret(ast)?.to_f64())
+        };
+        assert_eq!(eval("1 + 1").unwrap(), 2.0);
+        assert!(matches!(eval("1 $ 1"), Err(Error::Lexer(_))));
+        assert!(matches!(eval("1 +"), Err(Error::Syntax(_))));
+        let err = eval("sqrt(1, 2)").unwrap_err();
+        assert_eq!(err.code(), "E0206");
+        assert!(matches!(
+            err,
+            Error::Interpreter(err) if matches!(err.kind(), InterpreterErrorKind::ArityMismatch { found: 2, .. })
+        ));
+    }
+    #[test]
+    fn powers_are_written_as_they_would_be_typed() {
+        let not_real =
+            |base: &str| InterpreterErrorKind::NotReal { base: base.into(), exponent: "inf".into() }.to_string();
+        assert_eq!(not_real("-2"), "(-2)^inf is not a real number.");
+        assert_eq!(not_real("1/2"), "(1/2)^inf is not a real number.");
+        assert_eq!(not_real("2.5"), "2.5^inf is not a real number.");
+        let overflow = InterpreterErrorKind::Overflow { base: "-3 m".into(), exponent: "1e9".into() };
+        assert_eq!(overflow.to_string(), "(-3 m)^1e9 is too large to be computed.");
+    }
+}
@@ -0,0 +1,163 @@
+#[cfg(test)]
+mod tests {
+    use ralculator::expressions::interpreter::Interpreter;
+    use ralculator::expressions::lexer::Lexer;
+    use ralculator::expressions::parser::Parser;
+    use ralculator::errors::diagnostic::Span;
+    use ralculator::interface::editor::{EditorHelper, Style};
+
+    fn helper(lines: &[&str]) -> EditorHelper {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        for line in lines {
+            interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap()).unwrap();
+        }
+        let mut helper = EditorHelper::new(false);
+        helper.update(&parser, &interpreter);
+        helper
+    }
+
+    // The (displayed, inserted) candidates for the end of the line, and where the completed word starts.
+    fn complete(helper: &EditorHelper, line: &str) -> (usize, Vec<(String, String)>) {
+        let (start, candidates) = helper.candidates(line, line.len());
+        (start, candidates.into_iter().map(|pair| (pair.display, pair.replacement)).collect())
+    }
+
+    fn pair(display: &str, replacement: &str) -> (String, String) {
+        (display.to_string(), replacement.to_string())
+    }
+
+    #[test]
+    fn complete_variables_and_functions() {
+        let helper = helper(&["speed = 3", "spin = 2", "square(x) = x^2"]);
+        assert_eq!(
+            complete(&helper, "1 + sp"),
+            (4, vec![pair("speed", "speed"), pair("spin", "spin")])
+        );
+        assert_eq!(
+            complete(&helper, "sq"),
+            (0, vec![pair("sqrt(x)", "sqrt("), pair("square(x)", "square(")])
+        );
+        assert_eq!(complete(&helper, "2*(spe"), (3, vec![pair("speed", "speed")]));
+        assert_eq!(complete(&helper, "p"), (0, vec![pair("phi", "phi"), pair("pi", "pi")]));
+        assert_eq!(complete(&helper, "lo"), (0, vec![pair("log(base, x)", "log("), pair("log10(x)", "log10(")]));
+    }
+
+    #[test]
+    fn complete_in_the_middle_of_a_line() {
+        let helper = helper(&["speed = 3"]);
+        let (start, candidates) = helper.candidates("sp + 1", 2);
+        assert_eq!(start, 0);
+        assert_eq!(candidates[0].replacement, "speed");
+    }
+
+    #[test]
+    fn nothing_to_complete() {
+        let helper = helper(&["speed = 3"]);
+        assert_eq!(complete(&helper, ""), (0, vec![]));
+        assert_eq!(complete(&helper, "1 + "), (4, vec![]));
+        assert_eq!(complete(&helper, "zz"), (0, vec![]));
+        // Units follow numbers
+        assert_eq!(complete(&helper, "3 s"), (3, vec![]));
+    }
+
+    #[test]
+    fn complete_commands() {
+        let helper = helper(&["speed = 3", "sq(x) = x^2"]);
+        assert_eq!(complete(&helper, ":de"), (0, vec![
+            pair(":del name...", ":del"),
+            pair(":deps name", ":deps"),
+            pair(":dependents name", ":dependents"),
+        ]));
+        assert_eq!(complete(&helper, ":").1.len(), 9);
+        // Only what can be deleted is completed after :del
+        assert_eq!(
+            complete(&helper, ":del s"),
+            (5, vec![pair("speed", "speed"), pair("sq(x)", "sq")])
+        );
+        assert_eq!(complete(&helper, ":deps s"), (6, vec![pair("speed", "speed"), pair("sq(x)", "sq")]));
+    }
+
+    // The styled parts of the line, as text.
+    fn styles<'a>(helper: &EditorHelper, line: &'a str) -> Vec<(&'a str, Style)> {
+        helper.styles(line).into_iter().map(|(Span { start, end }, style)| (&line[start..end], style)).collect()
+    }
+
+    #[test]
+    fn highlight_tokens() {
+        let helper = helper(&["a = 2"]);
+        assert_eq!(
+            styles(&helper, "sqrt(a) * 3 km to m"),
+            vec![
+                ("sqrt", Style::Function),
+                ("(", Style::Separator),
+                ("a", Style::Variable),
+                (")", Style::Separator),
+                ("*", Style::Operator),
+                ("3", Style::Literal),
+                ("km", Style::Unit),
+                ("to", Style::Operator),
+                ("m", Style::Unit),
+            ]
+        );
+        assert_eq!(styles(&helper, ":ast pi")[..2], [(":ast", Style::Command), ("pi", Style::Variable)]);
+    }
+
+    #[test]
+    fn highlight_errors() {
+        let helper = helper(&["a = 2"]);
+        assert_eq!(
+            styles(&helper, "b $ a) + g(1"),
+            vec![
+                ("b", Style::Error),
+                ("$", Style::Error),
+                ("a", Style::Variable),
+                (")", Style::Error),
+                ("+", Style::Operator),
+                ("g", Style::Error),
+                ("(", Style::Error),
+                ("1", Style::Literal),
+            ]
+        );
+        assert_eq!(styles(&helper, "3 parsec")[1], ("parsec", Style::Error));
+    }
+
+    #[test]
+    fn names_being_defined_are_known() {
+        let helper = helper(&[]);
+        assert!(styles(&helper, "f(x, y) = x * y + g(x)").iter().all(|(text, style)| (*style == Style::Error) == (*text == "g")));
+        assert!(styles(&helper, "b = 1").iter().all(|(_, style)| *style != Style::Error));
+        assert_eq!(styles(&helper, "b = b")[2], ("b", Style::Error));
+    }
+
+    #[test]
+    fn names_defined_earlier_in_the_line_are_known() {
+        let helper = helper(&[]);
+        assert_eq!(
+            styles(&helper, "a = 1; a + b"),
+            vec![
+                ("a", Style::Variable),
+                ("=", Style::Operator),
+                ("1", Style::Literal),
+                (";", Style::Separator),
+                ("a", Style::Variable),
+                ("+", Style::Operator),
+                ("b", Style::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn hint_errors_of_complete_lines() {
+        let helper = helper(&["a = 2"]);
+        assert_eq!(helper.error_hint("a +"), None);
+        assert_eq!(helper.error_hint("sqrt(a"), None);
+        assert_eq!(helper.error_hint("a + 1"), None);
+        assert_eq!(helper.error_hint(":vars"), None);
+        assert_eq!(helper.error_hint("a $").unwrap(), "  ← unknown token '$'");
+        assert_eq!(helper.error_hint("a + b"), None);
+        assert!(helper.error_hint("a + b * 2").unwrap().contains("b"));
+        assert!(helper.error_hint("a)").is_some());
+    }
+}
@@ -1,6 +1,12 @@
 #[cfg(test)]
 mod tests {
-    use ralculator::expressions::interpreter::Interpreter;
+    use std::num::NonZeroU64;
+    use std::sync::{Arc, Mutex};
+    use ralculator::errors::diagnostic::{Diagnostic, Span};
+    use ralculator::errors::interpreter_error::{InterpreterError, InterpreterErrorKind};
+    use ralculator::expressions::interpreter::decimal::{DecimalContext, RoundingMode};
+    use ralculator::expressions::interpreter::number::{Backend, Notation, Number};
+    use ralculator::expressions::interpreter::{Binding, Interpreter};
     use ralculator::expressions::lexer::Lexer;
     use ralculator::expressions::parser::Parser;
 
@@ -38,6 +44,407 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn interpret_power() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let tokens = lexer.lex("2^3^2").unwrap();
+        let res = interpreter
+            .interpret(parser.parse(&tokens).unwrap())
+            .unwrap();
+        assert_eq!(res, 512.0);
+        let tokens = lexer.lex("-2^2").unwrap();
+        let res = interpreter
+            .interpret(parser.parse(&tokens).unwrap())
+            .unwrap();
+        assert_eq!(res, -4.0);
+        let tokens = lexer.lex("2 * 3^2").unwrap();
+        let res = interpreter
+            .interpret(parser.parse(&tokens).unwrap())
+            .unwrap();
+        assert_eq!(res, 18.0);
+    }
+
+    #[test]
+    fn cannot_raise_zero_to_negative_power() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let tokens = lexer.lex("0^-1").unwrap();
+        let res = interpreter.interpret(parser.parse(&tokens).unwrap());
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn interpret_builtins() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| {
+            interpreter
+                .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                .unwrap()
+        };
+        assert_eq!(eval("sqrt(16) + abs(-2)"), 6.0);
+        assert_eq!(eval("log(2, 8)"), 3.0);
+        assert_eq!(eval("max(1, 5, 3) - min(4, 2)"), 3.0);
+        assert_eq!(eval("floor(2.7) + ceil(2.2) + round(2.5)"), 8.0);
+        assert_eq!(eval("ln(exp(2))"), 2.0);
+        assert_eq!(format!("{:.4}", eval("atan2(1, 1) * 4")), "3.1416");
+    }
+
+    #[test]
+    fn builtins_check_arity_and_domain() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        for line in ["sqrt(1, 2)", "atan2(1)", "max()", "ln(-1)", "ln(0)", "log(1, 5)", "asin(2)"] {
+            let res = interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+            assert!(res.is_err(), "{line} should fail");
+        }
+    }
+
+    #[test]
+    fn interpret_user_functions() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        eval("f(x, y) = x^2 + y").unwrap();
+        assert_eq!(eval("f(3, 1)").unwrap(), 10.0);
+        assert_eq!(eval("f(f(1, 1), 0) * 2").unwrap(), 8.0);
+        eval("x = 10").unwrap();
+        eval("y = x").unwrap();
+        eval("g(x) = x + y").unwrap(); // y is evaluated with the global x, not the parameter
+        assert_eq!(eval("g(1)").unwrap(), 11.0);
+        eval("f(x, y) = x - y").unwrap();
+        assert_eq!(eval("f(3, 1)").unwrap(), 2.0);
+    }
+
+    #[test]
+    fn user_functions_errors() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        eval("f(x, y) = x + y").unwrap();
+        assert!(eval("f(1)").is_err());
+        assert!(eval("f(1, 2, 3)").is_err());
+        assert!(eval("sqrt(x) = x").is_err());
+        assert!(eval("g(sqrt) = 1").is_err());
+        assert!(eval("sqrt = 2").is_err());
+        eval("a = 1").unwrap();
+        eval("h(x) = x + a").unwrap();
+        assert!(eval("a = h(1)").is_err());
+    }
+
+    #[test]
+    fn interpret_programs() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |source: &str| interpreter.interpret(parser.parse(&lexer.lex(source).unwrap()).unwrap());
+        assert_eq!(eval("a = 2; f(x) = x * a\nf(3) + 1").unwrap(), 7.0);
+        assert_eq!(eval("a").unwrap(), 2.0);
+        // Statements are evaluated until one fails, whose span the error has
+        let err = eval("b = 1; 1 / 0; a = 5").unwrap_err();
+        assert_eq!(err.kind(), &InterpreterErrorKind::DivisionByZero);
+        assert_eq!(err.span(), Some(Span::new(7, 12)));
+        assert_eq!(eval("a").unwrap(), 2.0);
+        assert_eq!(eval("b").unwrap(), 1.0);
+    }
+
+    #[test]
+    fn errors_have_a_kind() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        eval("a = 1").unwrap();
+        eval("b = a + 1").unwrap();
+        eval("f(x) = x * b").unwrap();
+        assert_eq!(
+            eval("a = f(2)").unwrap_err().kind(),
+            &InterpreterErrorKind::CyclicDefinition { cycle: vec!["a".into(), "f".into(), "b".into(), "a".into()] }
+        );
+        assert_eq!(eval("1 / 0").unwrap_err().kind(), &InterpreterErrorKind::DivisionByZero);
+        assert_eq!(eval("pi = 3").unwrap_err().kind(), &InterpreterErrorKind::RedefinedConstant { name: "pi".into() });
+        assert_eq!(
+            eval("f(1, 2)").unwrap_err().kind(),
+            &InterpreterErrorKind::ArityMismatch { function: "f(x)".into(), expected: "1 argument(s)".into(), found: 2 }
+        );
+        assert!(matches!(eval("ln(-1)").unwrap_err().kind(), InterpreterErrorKind::OutOfDomain { function, .. } if function == "ln"));
+        assert!(matches!(eval("1 m + 1 s").unwrap_err().kind(), InterpreterErrorKind::DimensionMismatch { .. }));
+    }
+
+    #[test]
+    fn recursion_is_limited() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .interpret(parser.parse(&lexer.lex("f(x) = f(x + 1)").unwrap()).unwrap())
+            .unwrap();
+        assert!(interpreter
+            .interpret(parser.parse(&lexer.lex("f(1)").unwrap()).unwrap())
+            .is_err());
+        interpreter.set_max_call_depth(3);
+        interpreter
+            .interpret(parser.parse(&lexer.lex("g(x) = x + 1").unwrap()).unwrap())
+            .unwrap();
+        assert_eq!(
+            interpreter
+                .interpret(parser.parse(&lexer.lex("g(g(g(1)))").unwrap()).unwrap())
+                .unwrap(),
+            4.0
+        );
+        for line in ["h(x) = g(x)", "k(x) = h(x)", "m(x) = k(x)"] {
+            interpreter
+                .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                .unwrap();
+        }
+        assert_eq!(
+            interpreter
+                .interpret(parser.parse(&lexer.lex("k(1)").unwrap()).unwrap())
+                .unwrap(),
+            2.0
+        );
+        assert!(interpreter
+            .interpret(parser.parse(&lexer.lex("m(1)").unwrap()).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn interpret_constants() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        assert_eq!(eval("tau - 2 * pi").unwrap(), 0.0);
+        assert_eq!(eval("ln(e)").unwrap(), 1.0);
+        assert_eq!(format!("{:.6}", eval("phi").unwrap()), "1.618034");
+        assert_eq!(eval("-inf").unwrap(), f64::NEG_INFINITY);
+        assert!(eval("nan").unwrap().is_nan());
+        eval("r = 2").unwrap();
+        assert_eq!(format!("{:.4}", eval("2 * pi * r").unwrap()), "12.5664");
+    }
+
+    #[test]
+    fn constants_are_protected() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        assert!(eval("pi = 3").is_err());
+        assert!(eval("e(x) = x").is_err());
+        assert!(eval("f(tau) = tau").is_err());
+        assert_eq!(eval("pi").unwrap(), std::f64::consts::PI);
+    }
+
+    #[test]
+    fn interpret_rationals() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::with_backend(Backend::Rational);
+        let mut eval = |line: &str| {
+            interpreter
+                .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                .unwrap()
+        };
+        assert_eq!(eval("1/3 * 3").to_string(), "1");
+        assert_eq!(eval("0.1 + 0.2").to_string(), "3/10");
+        assert_eq!(eval("2^-2").to_string(), "1/4");
+        assert_eq!(eval("sqrt(9/4)").to_string(), "3/2");
+        assert_eq!(eval("1.5e-3").to_string(), "3/2000");
+        assert!(eval("1/3").is_exact());
+        assert!(!eval("sqrt(2)").is_exact());
+        assert!(!eval("2 * pi").is_exact());
+    }
+
+    #[test]
+    fn display_rationals_as_decimals() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::with_backend(Backend::Rational);
+        let mut eval = |line: &str| {
+            interpreter
+                .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                .unwrap()
+        };
+        assert_eq!(format!("{:#}", eval("0.1 + 0.2")), "0.3");
+        assert_eq!(format!("{:#}", eval("-7/8")), "-0.875");
+        assert_eq!(format!("{:#}", eval("1/3")), "0.33333333333333333333...");
+        assert_eq!(format!("{:.2}", eval("2/3")), "0.67");
+    }
+
+    #[test]
+    fn interpret_decimals() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::with_backend(Backend::Decimal(DecimalContext::default()));
+        let mut eval = |line: &str| {
+            interpreter
+                .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(eval("0.1 + 0.2"), "0.3");
+        assert_eq!(eval("10 / 4"), "2.5");
+        assert_eq!(eval("2^-3"), "0.125");
+        assert_eq!(eval("1/3"), "0.33333333333333333333333333333333333333333333333333");
+        assert_eq!(eval("sqrt(2)"), "1.4142135623730950488016887242096980785696718753769");
+        assert_eq!(eval("2^0.5"), eval("sqrt(2)"));
+        assert_eq!(eval("pi"), "3.1415926535897932384626433832795028841971693993751");
+        assert_eq!(eval("e"), "2.7182818284590452353602874713526624977572470937");
+        assert_eq!(eval("ln(2)"), "0.69314718055994530941723212145817656807550013436026");
+        assert_eq!(eval("sin(1)"), "0.84147098480789650665250232163029899962256306079837");
+        assert_eq!(eval("atan2(1, 1) * 4"), eval("pi"));
+        assert_eq!(eval("log(2, 1024)"), "10");
+        assert_eq!(eval("2^1000"), "1.0715086071862673209484250490600018105614048117055e301");
+    }
+
+    #[test]
+    fn decimals_bound_huge_arguments() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::with_backend(Backend::Decimal(DecimalContext::default()));
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        for line in ["sin(1e5000)", "cos(1e5000)", "tan(1e5000)"] {
+            assert!(matches!(eval(line).unwrap_err().kind(), InterpreterErrorKind::Undefined { .. }), "{line}");
+        }
+        assert_eq!(eval("2^1e5").unwrap().to_string(), "9.9900209301438450794403276433003359098042913905418e30102");
+        assert_eq!(eval("2^100000.5").unwrap().to_string(), "1.4128023087800507668633388384012386830125868792506e30103");
+        assert_eq!(eval("1.0000001^1e10").unwrap().to_string(), "1.9699726129304605663092499948461492718403216895148e434");
+    }
+
+    #[test]
+    fn decimals_follow_precision_and_rounding() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let precision = NonZeroU64::new(5).unwrap();
+        for (rounding, minus_one_third, two_thirds) in [
+            (RoundingMode::HalfEven, "-0.33333", "0.66667"),
+            (RoundingMode::Down, "-0.33333", "0.66666"),
+            (RoundingMode::Up, "-0.33334", "0.66667"),
+            (RoundingMode::Floor, "-0.33334", "0.66666"),
+        ] {
+            let mut interpreter = Interpreter::with_backend(Backend::Decimal(DecimalContext::new(precision, rounding)));
+            let mut eval = |line: &str| {
+                interpreter
+                    .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                    .unwrap()
+                    .to_string()
+            };
+            assert_eq!(eval("-1/3"), minus_one_third);
+            assert_eq!(eval("2/3"), two_thirds);
+        }
+    }
+
+    #[test]
+    fn interpret_complex_numbers() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| {
+            interpreter
+                .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                .unwrap()
+        };
+        assert_eq!(eval("sqrt(-1)").to_string(), "i");
+        assert_eq!(eval("sqrt(-4) * 1i").to_string(), "-2");
+        assert_eq!(eval("(3+4i) * (1-2i)").to_string(), "11-2i");
+        assert_eq!(eval("(1+2i) / (3-4i)").to_string(), "-0.2+0.4i");
+        assert_eq!(eval("1i^2").to_string(), "-1");
+        assert_eq!(eval("abs(3+4i)"), 5.0);
+        assert_eq!(eval("re(3+4i) + im(3-4i)"), -1.0);
+        assert_eq!(eval("conj(3+4i)").to_string(), "3-4i");
+        assert_eq!(eval("arg(-1)"), std::f64::consts::PI);
+        assert_eq!(format!("{:.4}", eval("arg(1+1i)")), "0.7854");
+        assert_eq!(format!("{:.3}", eval("exp(1i)")), "0.540+0.841i");
+        assert_eq!(eval("i^2").to_string(), "-1");
+        assert_eq!(eval("2 + i").to_string(), "2+i");
+        assert_eq!(eval("(0-2)^0.5").to_string(), "1.4142135623730951i");
+        assert_eq!(format!("{:.3}", eval("(-8)^(1/3)")), "1.000+1.732i");
+    }
+
+    #[test]
+    fn display_complex_numbers() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| {
+            interpreter
+                .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                .unwrap()
+        };
+        let polar = Notation { polar: true, ..Notation::default() };
+        assert_eq!(eval("-1i").to_string(), "-i");
+        assert_eq!(eval("2.5i").to_string(), "2.5i");
+        assert_eq!(eval("3+4i").display(polar), "5∠0.9272952180016122");
+        assert_eq!(eval("3").display(polar), "3");
+    }
+
+    #[test]
+    fn complex_numbers_are_not_ordered() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        for line in ["max(1i, 2)", "floor(1.5i)", "atan2(1i, 1)"] {
+            let res = interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+            assert!(res.is_err(), "{line} should fail");
+        }
+    }
+
+    #[test]
+    fn interpret_units() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| {
+            interpreter
+                .interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(eval("3 km + 200 m"), "3.2 km");
+        assert_eq!(eval("60 mph to m/s"), "26.8224 m/s");
+        assert_eq!(eval("10 m / 2 s"), "5 m/s");
+        assert_eq!(eval("(3 m)^2"), "9 m^2");
+        assert_eq!(eval("sqrt(9 m^2)"), "3 m");
+        assert_eq!(eval("2 km / 1 m"), "2000");
+        assert_eq!(eval("5 kg*m/s^2 to N"), "5 N");
+        assert_eq!(eval("1 kW*h in J"), "3600000 J");
+        assert_eq!(eval("3 in to cm"), "7.62 cm");
+        assert_eq!(eval("1 µm to nm"), "1000 nm");
+        assert_eq!(eval("5 µm to m"), "0.000005 m");
+        assert_eq!(eval("1 m + 5 µm"), "1.000005 m");
+        assert_eq!(eval("1 s^-1"), "1 s^-1");
+        assert_eq!(eval("max(1 km, 500 m)"), "1 km");
+    }
+
+    #[test]
+    fn units_are_exact_with_the_rational_backend() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::with_backend(Backend::Rational);
+        let res = interpreter
+            .interpret(parser.parse(&lexer.lex("1 km + 1 m to m").unwrap()).unwrap())
+            .unwrap();
+        assert_eq!(res.to_string(), "1001 m");
+    }
+
+    #[test]
+    fn dimensions_must_match() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        for line in ["3 m + 2 s", "1 - 1 m", "5 m to s", "2^(1 m)", "sqrt(2 m)", "sin(3 m)", "max(1 m, 1 s)"] {
+            let res = interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+            assert!(res.is_err(), "{line} should fail");
+        }
+    }
+
     #[test]
     fn keeps_track_of_vars() {
         let lexer = Lexer::new();
@@ -87,4 +494,230 @@ mod tests {
             .interpret(parser.parse(&lexer.lex("a = b").unwrap()).unwrap())
             .is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn cycles_are_reported_with_their_path() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        eval("a = 1; b = a; c = b + 1; d = c * 2").unwrap();
+        let cycle = |names: &[&str]| InterpreterErrorKind::CyclicDefinition {
+            cycle: names.iter().map(ToString::to_string).collect(),
+        };
+        assert_eq!(eval("a = d").unwrap_err().kind(), &cycle(&["a", "d", "c", "b", "a"]));
+        assert_eq!(eval("a = 2 * (1 + d)").unwrap_err().to_string(), "Illegal assignation: cyclic definition a -> d -> c -> b -> a");
+        assert_eq!(eval("b = b").unwrap_err().kind(), &cycle(&["b", "b"]));
+        // Through functions, recursive ones included
+        eval("f(x) = x * c; g(n) = g(n - 1) + f(n)").unwrap();
+        assert_eq!(eval("a = g(3)").unwrap_err().kind(), &cycle(&["a", "g", "f", "c", "b", "a"]));
+        // Functions closing a cycle through a variable, but not through functions only
+        eval("h(x) = x; k = h(1)").unwrap();
+        assert_eq!(eval("h(x) = k").unwrap_err().kind(), &cycle(&["h", "k", "h"]));
+        assert_eq!(eval("h(x) = f(x) + k").unwrap_err().kind(), &cycle(&["h", "k", "h"]));
+        assert_eq!(eval("k").unwrap(), 1.0);
+        eval("odd(n) = n; even(n) = odd(n - 1); odd(n) = even(n - 1) + h(n)").unwrap();
+        // A failed definition is not kept
+        assert_eq!(eval("d").unwrap(), 4.0);
+        // Redefining a variable breaks the cycles going through its previous definition
+        eval("b = 5").unwrap();
+        assert_eq!(eval("a = d").unwrap(), 12.0);
+    }
+
+    #[test]
+    fn unknown_names_never_panic() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        eval("a = 1; b = a + 1; f(x) = x + b").unwrap();
+        assert!(interpreter.remove("a"));
+        assert!(interpreter.remove("f"));
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        assert_eq!(eval("c = b * f(2)").unwrap_err().kind(), &InterpreterErrorKind::UndefinedVariable { name: "a".into() });
+        assert!(matches!(eval("a = b").unwrap_err().kind(), InterpreterErrorKind::CyclicDefinition { cycle } if cycle.len() == 3));
+        assert_eq!(eval("a = 2").unwrap(), 2.0);
+        assert_eq!(eval("b").unwrap(), 3.0);
+    }
+
+    #[test]
+    fn dependencies_follow_the_definitions() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        eval("r = 2; area(x) = pi * x^2 * r; a = area(r) + sqrt(r); b = r").unwrap();
+        let graph = interpreter.dependency_graph();
+        // Parameters, builtin functions and constants aren't dependencies
+        assert_eq!(graph.dependencies("a"), ["area", "r"]);
+        assert_eq!(graph.dependencies("area"), ["r"]);
+        assert_eq!(graph.dependencies("r"), Vec::<&str>::new());
+        assert_eq!(graph.dependents("r"), ["a", "area", "b"]);
+        assert_eq!(graph.names().into_iter().collect::<Vec<_>>(), ["a", "area", "b", "r"]);
+        interpreter.remove("a");
+        assert_eq!(interpreter.dependency_graph().dependents("r"), ["area", "b"]);
+        interpreter.clear();
+        assert!(interpreter.dependency_graph().names().is_empty());
+    }
+
+    #[test]
+    fn transitive_dependencies_and_dependents() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        eval("a = 1; b = a + 1; f(x) = x * b; c = f(2); d = c + a; k = 5").unwrap();
+        assert_eq!(interpreter.dependencies_of("d"), ["a", "b", "c", "f"]);
+        assert_eq!(interpreter.dependents_of("a"), ["b", "c", "d", "f"]);
+        assert_eq!(interpreter.dependents_of("d"), Vec::<&str>::new());
+        assert_eq!(interpreter.dependencies_of("k"), Vec::<&str>::new());
+        assert_eq!(interpreter.dependencies_of("nothing"), Vec::<&str>::new());
+        // A name is among its own dependencies only on a cycle
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        eval("g(n) = g(n - 1) + a").unwrap();
+        assert_eq!(interpreter.dependencies_of("g"), ["a", "g"]);
+        interpreter.remove("b");
+        assert_eq!(interpreter.dependencies_of("d"), ["a", "b", "c", "f"]);
+        assert!(interpreter.dependency_dot().contains("    \"b\" [style=dashed];\n"));
+    }
+
+    #[test]
+    fn bindings_are_frozen() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        assert_eq!(eval("n = 1; start := n * 10; live = n * 10").unwrap(), 10.0);
+        eval("n = 5").unwrap();
+        assert_eq!(eval("start").unwrap(), 10.0);
+        assert_eq!(eval("live").unwrap(), 50.0);
+        // The previous value can be read, so bindings can count
+        assert_eq!(eval("c := 0; c := c + 1; c := c + 1").unwrap(), 2.0);
+        // and nothing is bound when the expression fails
+        assert_eq!(eval("c := c / 0").unwrap_err().kind(), &InterpreterErrorKind::DivisionByZero);
+        assert_eq!(eval("c").unwrap(), 2.0);
+        assert!(eval("pi := 3").is_err());
+        // A frozen variable depends on nothing, so it never closes a cycle
+        assert_eq!(interpreter.dependencies_of("start"), Vec::<&str>::new());
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        assert_eq!(eval("n = start + 1").unwrap(), 11.0);
+        let variables = interpreter.variables();
+        assert!(matches!(variables[0], ("c", Binding::Frozen(_))));
+        assert!(matches!(variables[2], ("n", Binding::Lazy(_))));
+    }
+
+    #[test]
+    fn variables_are_evaluated_once_until_a_dependency_changes() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        // Each level reads the previous one twice: without caching, v60 would take 2^60 evaluations
+        eval("v0 = 1").unwrap();
+        for level in 1..=60 {
+            eval(&format!("v{level} = v{} + v{}", level - 1, level - 1)).unwrap();
+        }
+        assert_eq!(eval("v60").unwrap(), 2f64.powi(60));
+        // Reassigning the root invalidates the whole chain, and only it
+        eval("other = v3 * 10").unwrap();
+        eval("v0 = 3").unwrap();
+        assert_eq!(eval("v60").unwrap(), 3.0 * 2f64.powi(60));
+        assert_eq!(eval("other").unwrap(), 240.0);
+        // So does redefining a function, binding a value or deleting a variable
+        eval("f(x) = x + v0; w = f(1) * 2").unwrap();
+        assert_eq!(eval("w").unwrap(), 8.0);
+        eval("f(x) = x - v0").unwrap();
+        assert_eq!(eval("w").unwrap(), -4.0);
+        eval("v0 := 10").unwrap();
+        assert_eq!(eval("w").unwrap(), -18.0);
+        assert_eq!(eval("v2").unwrap(), 40.0);
+        interpreter.remove("v0");
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap());
+        assert_eq!(eval("v2").unwrap_err().kind(), &InterpreterErrorKind::UndefinedVariable { name: "v0".into() });
+        assert_eq!(eval("v0 = 1; v2").unwrap(), 4.0);
+        // Parameters still shadow the variables of the same name
+        assert_eq!(eval("g(v2) = v2 + 1; g(10)").unwrap(), 11.0);
+    }
+
+    // A callback recording the changes it is told about as `name: value`
+    fn record(changes: &Arc<Mutex<Vec<String>>>) -> impl FnMut(&str, &Result<Number, InterpreterError>) + Send {
+        let changes = Arc::clone(changes);
+        move |name, value| {
+            let value = value.as_ref().map_or_else(|_| String::from("error"), ToString::to_string);
+            changes.lock().unwrap().push(format!("{name}: {value}"));
+        }
+    }
+
+    #[test]
+    fn subscribers_are_told_when_their_variable_changes() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        // Variables can be subscribed to before they are defined
+        let total = interpreter.subscribe("total", record(&changes));
+        interpreter.subscribe("price", record(&changes));
+        let mut eval = |line: &str| interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap()).unwrap();
+        eval("price = 10; tax(x) = x / 5; total = price + tax(price)");
+        eval("price = 20");
+        // Redefining a function changes what is defined in terms of it, unrelated definitions change nothing
+        eval("tax(x) = x / 4");
+        eval("other = 3; price = 40 / 2");
+        assert_eq!(*changes.lock().unwrap(), ["price: 10", "total: 12", "price: 20", "total: 24", "total: 25"]);
+        changes.lock().unwrap().clear();
+        // Frozen bindings don't follow what they were computed from
+        eval("total := price * 2");
+        eval("price = 1");
+        assert_eq!(*changes.lock().unwrap(), ["total: 40", "price: 1"]);
+        changes.lock().unwrap().clear();
+        assert!(interpreter.unsubscribe(total));
+        assert!(!interpreter.unsubscribe(total));
+        interpreter.remove("price");
+        assert_eq!(*changes.lock().unwrap(), ["price: error"]);
+    }
+
+    #[test]
+    fn subscribers_are_told_when_their_variable_fails() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let mut eval = |interpreter: &mut Interpreter, line: &str| {
+            interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap())
+        };
+        eval(&mut interpreter, "b = 1; a = b + 1").unwrap();
+        interpreter.subscribe("a", record(&changes));
+        interpreter.subscribe("b", record(&changes));
+        let err = eval(&mut interpreter, "b = 1/0").unwrap_err();
+        assert_eq!(err.kind(), &InterpreterErrorKind::DivisionByZero);
+        eval(&mut interpreter, "b = 2").unwrap();
+        assert_eq!(*changes.lock().unwrap(), ["a: error", "b: error", "a: 3", "b: 2"]);
+    }
+
+    #[test]
+    fn subscriptions_stay_with_their_interpreter() {
+        let lexer = Lexer::new();
+        let mut parser = Parser::new();
+        let mut interpreter = Interpreter::new();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let mut eval = |interpreter: &mut Interpreter, line: &str| {
+            interpreter.interpret(parser.parse(&lexer.lex(line).unwrap()).unwrap()).unwrap()
+        };
+        eval(&mut interpreter, "price = 1");
+        interpreter.subscribe("price", record(&changes));
+        // A clone has no subscribers
+        let mut clone = interpreter.clone();
+        eval(&mut clone, "price = 2");
+        assert!(changes.lock().unwrap().is_empty());
+        // Rolling back tells the subscribers about the values put back
+        eval(&mut interpreter, "price = 5");
+        interpreter.restore(clone);
+        assert_eq!(*changes.lock().unwrap(), ["price: 5", "price: 2"]);
+        assert_eq!(eval(&mut interpreter, "price * 3"), 6.0);
+        // Interpreters, with their subscriptions, can be moved to other threads
+        eval(&mut interpreter, "price = 7");
+        let thread = std::thread::spawn(move || interpreter.remove("price"));
+        assert!(thread.join().unwrap());
+        assert_eq!(*changes.lock().unwrap(), ["price: 5", "price: 2", "price: 7", "price: error"]);
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use ralculator::errors::diagnostic::Span;
+    use ralculator::errors::interpreter_error::InterpreterErrorKind;
+    use ralculator::errors::lexer_error::LexerErrorKind;
+    use ralculator::expressions::interpreter::number::Notation;
+    use ralculator::expressions::interpreter::Interpreter;
+    use ralculator::expressions::lexer::Lexer;
+    use ralculator::expressions::parser::Parser;
+    use ralculator::interface::json::{ErrorKind, Record};
+    use ralculator::interface::repl::Repl;
+    use ralculator::interface::runner;
+
+    // Evaluates the line in a new session, returning the record of its last statement.
+    fn record(line: &str) -> Record {
+        let (mut parser, mut interpreter) = (Parser::new(), Interpreter::new());
+        let outcome = runner::run(line, (&Lexer::new(), &mut parser, &mut interpreter), false).pop().unwrap();
+        match outcome.result {
+            Ok(value) => outcome.record.with_value(&value, Notation::default()),
+            Err(err) => outcome.record.with_error(&err, line),
+        }
+    }
+
+    #[test]
+    fn records_have_the_value_and_its_type() {
+        assert_eq!(
+            record("x = 2 km").to_string(),
+            r#"{"input":"x = 2 km","value":"2 km","type":"quantity","variable":"x","error":null}"#
+        );
+        assert_eq!(
+            record("f(x) = x + 1").to_string(),
+            r#"{"input":"f(x) = x + 1","value":null,"type":"function","variable":"f","error":null}"#
+        );
+        let complex = record("sqrt(-1)");
+        assert_eq!((complex.value.as_deref(), complex.value_type), (Some("i"), Some("complex")));
+        assert_eq!(record("1 + 1").value_type, Some("float"));
+    }
+
+    #[test]
+    fn records_of_errors_have_their_kind_and_position() {
+        let error = record("1 + 2 $").error.unwrap();
+        assert_eq!((error.stage, error.code), ("lexer", "E0001"));
+        assert_eq!(error.kind, ErrorKind::Lexer(LexerErrorKind::UnknownToken { found: '$' }));
+        assert_eq!(error.span, Some(Span::new(6, 7)));
+        assert_eq!((error.line, error.column), (1, 7));
+        let error = record("1 + zz").error.unwrap();
+        assert_eq!((error.stage, error.help.as_deref()), ("syntax", Some("define it first, e.g. zz = 1")));
+        assert_eq!(
+            record("1 / 0").to_string(),
+            r#"{"input":"1 / 0","value":null,"type":null,"variable":null,"error":{"stage":"interpreter","kind":"division_by_zero","code":"E0203","message":"Cannot divide by 0.","help":null,"span":{"start":0,"end":5},"line":1,"column":1}}"#
+        );
+        // Failing assignments and bindings still have their variable
+        for line in ["x = 1 / 0", "x := 1 / 0", "x = 1 +"] {
+            let record = record(line);
+            assert_eq!((record.variable.as_deref(), record.value_type), (Some("x"), None), "{line}");
+            assert!(record.error.is_some());
+        }
+        assert_eq!(record("f(x) = y").variable, None);
+    }
+
+    #[test]
+    fn records_of_errors_have_the_details_of_their_kind() {
+        // Kinds sharing a code are told apart
+        let error = record("0^-1").error.unwrap();
+        let kind = ErrorKind::Interpreter(InterpreterErrorKind::NegativePowerOfZero);
+        assert_eq!((error.code, error.kind), ("E0203", kind));
+        let json = record("a = 1; b = a; a = b").to_string();
+        let cycle = r#""stage":"interpreter","kind":"cyclic_definition","cycle":["a","b","a"],"code":"E0201""#;
+        assert!(json.contains(cycle));
+        assert!(record("1 + zz").to_string().contains(r#""kind":"unknown_symbol","name":"zz""#));
+        let json = record("1 m + 1 s").to_string();
+        let mismatch = r#""kind":"dimension_mismatch","operation":"add","left":"1 m","right":"1 s","expected":"length""#;
+        assert!(json.contains(mismatch) && json.contains(r#""found":"time","code":"E0209""#));
+    }
+
+    #[test]
+    fn json_sessions_write_a_record_per_statement() {
+        let mut repl = Repl::new(Lexer::new(), Parser::new(), Interpreter::new());
+        repl.set_json(true);
+        let (mut output, mut errors) = (Vec::new(), Vec::new());
+        let input = "a = 2; a * 3\n\nb = (a +\n 1)\n1 / 0\nb\n";
+        repl.session(input.as_bytes(), &mut output, &mut errors).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let inputs = output
+            .lines()
+            .map(|line| line.split('"').nth(3).unwrap())
+            .collect::<Vec<_>>();
+        // Empty lines don't end the session, and there are no prompts
+        assert_eq!(inputs, ["a = 2", "a * 3", "b = (a +\\n 1)", "1 / 0", "b"]);
+        assert!(output.lines().nth(1).unwrap().contains(r#""value":"6""#));
+        assert!(output.lines().nth(3).unwrap().contains(r#""stage":"interpreter","kind":"division_by_zero""#));
+        assert!(errors.is_empty());
+    }
+}
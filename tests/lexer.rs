@@ -1,5 +1,7 @@
 #[cfg(test)]
 mod tests {
+    use ralculator::errors::diagnostic::{Diagnostic, Span};
+    use ralculator::errors::lexer_error::LexerErrorKind;
     use ralculator::expressions::lexer::tokens::Token;
     use ralculator::expressions::lexer::tokens::Kind;
     use ralculator::expressions::lexer::Lexer;
@@ -55,4 +57,166 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn lex_function_call() {
+        let lexer = Lexer::new();
+        assert_eq!(
+            lexer.lex("atan2(1, x_2)").unwrap(),
+            vec![
+                Token::new(Kind::Identifier, "atan2".into(), 0),
+                Token::new(Kind::Separator, "(".into(), 5),
+                Token::new(Kind::Literal, "1".into(), 6),
+                Token::new(Kind::Separator, ",".into(), 7),
+                Token::new(Kind::Identifier, "x_2".into(), 9),
+                Token::new(Kind::Separator, ")".into(), 12)
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_imaginary_literal() {
+        let lexer = Lexer::new();
+        assert_eq!(
+            lexer.lex("3+4.5i*in").unwrap(),
+            vec![
+                Token::new(Kind::Literal, "3".into(), 0),
+                Token::new(Kind::Operator, "+".into(), 1),
+                Token::new(Kind::Literal, "4.5i".into(), 2),
+                Token::new(Kind::Operator, "*".into(), 6),
+                Token::new(Kind::Identifier, "in".into(), 7)
+            ]
+        );
+        assert_eq!(
+            lexer.lex("2in").unwrap(),
+            vec![
+                Token::new(Kind::Literal, "2".into(), 0),
+                Token::new(Kind::Unit, "in".into(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn positions_include_leading_whitespaces() {
+        let lexer = Lexer::new();
+        assert_eq!(lexer.lex("  1").unwrap(), vec![Token::new(Kind::Literal, "1".into(), 2)]);
+        let err = lexer.lex("  1 $ 2").unwrap_err();
+        assert_eq!(err.kind(), &LexerErrorKind::UnknownToken { found: '$' });
+        assert_eq!(err.span(), Some(Span::new(4, 5)));
+    }
+
+    #[test]
+    fn comments_are_skipped() {
+        let lexer = Lexer::new();
+        assert_eq!(lexer.lex("# only a comment").unwrap(), vec![]);
+        assert_eq!(
+            lexer.lex("1 # one\n  # nothing\n2 km # two").unwrap(),
+            vec![
+                Token::new(Kind::Literal, "1".into(), 0),
+                Token::new(Kind::Terminator, "\n".into(), 7),
+                Token::new(Kind::Terminator, "\n".into(), 19),
+                Token::new(Kind::Literal, "2".into(), 20),
+                Token::new(Kind::Unit, "km".into(), 22),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_statement_terminators() {
+        let lexer = Lexer::new();
+        assert_eq!(
+            lexer.lex("a = 1; 2 \n3").unwrap(),
+            vec![
+                Token::new(Kind::Identifier, "a".into(), 0),
+                Token::new(Kind::Operator, "=".into(), 2),
+                Token::new(Kind::Literal, "1".into(), 4),
+                Token::new(Kind::Terminator, ";".into(), 5),
+                Token::new(Kind::Literal, "2".into(), 7),
+                Token::new(Kind::Terminator, "\n".into(), 9),
+                Token::new(Kind::Literal, "3".into(), 10),
+            ]
+        );
+        // Newlines don't end statements within parentheses, nor at the end of the input
+        assert_eq!(
+            lexer.lex("f(1,\n 2)\n\n").unwrap(),
+            vec![
+                Token::new(Kind::Identifier, "f".into(), 0),
+                Token::new(Kind::Separator, "(".into(), 1),
+                Token::new(Kind::Literal, "1".into(), 2),
+                Token::new(Kind::Separator, ",".into(), 3),
+                Token::new(Kind::Literal, "2".into(), 6),
+                Token::new(Kind::Separator, ")".into(), 7),
+            ]
+        );
+        // A unit doesn't continue on the next line
+        assert_eq!(
+            lexer.lex("3 km\nm").unwrap()[2..],
+            [Token::new(Kind::Terminator, "\n".into(), 4), Token::new(Kind::Identifier, "m".into(), 5)]
+        );
+    }
+
+    #[test]
+    fn lex_binding_operator() {
+        let lexer = Lexer::new();
+        assert_eq!(
+            lexer.lex("a:=b =1").unwrap(),
+            vec![
+                Token::new(Kind::Identifier, "a".into(), 0),
+                Token::new(Kind::Operator, ":=".into(), 1),
+                Token::new(Kind::Identifier, "b".into(), 3),
+                Token::new(Kind::Operator, "=".into(), 5),
+                Token::new(Kind::Literal, "1".into(), 6),
+            ]
+        );
+        assert_eq!(lexer.lex("a : = 1").unwrap_err().kind(), &LexerErrorKind::UnknownToken { found: ':' });
+    }
+
+    #[test]
+    fn lex_units_and_conversions() {
+        let lexer = Lexer::new();
+        assert_eq!(
+            lexer.lex("60 mph to m/s^2").unwrap(),
+            vec![
+                Token::new(Kind::Literal, "60".into(), 0),
+                Token::new(Kind::Unit, "mph".into(), 3),
+                Token::new(Kind::Operator, "to".into(), 7),
+                Token::new(Kind::Unit, "m/s^2".into(), 10)
+            ]
+        );
+        assert_eq!(
+            lexer.lex("3 in in cm").unwrap(),
+            vec![
+                Token::new(Kind::Literal, "3".into(), 0),
+                Token::new(Kind::Unit, "in".into(), 2),
+                Token::new(Kind::Operator, "in".into(), 5),
+                Token::new(Kind::Unit, "cm".into(), 8)
+            ]
+        );
+        let err = lexer.lex("1 km to parsec").unwrap_err();
+        assert!(matches!(err.kind(), LexerErrorKind::UnknownUnit { unit, .. } if unit == "parsec"));
+        assert_eq!(err.span(), Some(Span::new(8, 14)));
+    }
+
+    #[test]
+    fn units_only_follow_numbers() {
+        let lexer = Lexer::new();
+        assert_eq!(
+            lexer.lex("6 m/x").unwrap(),
+            vec![
+                Token::new(Kind::Literal, "6".into(), 0),
+                Token::new(Kind::Unit, "m".into(), 2),
+                Token::new(Kind::Operator, "/".into(), 3),
+                Token::new(Kind::Identifier, "x".into(), 4)
+            ]
+        );
+        assert_eq!(
+            lexer.lex("m * 2 s").unwrap(),
+            vec![
+                Token::new(Kind::Identifier, "m".into(), 0),
+                Token::new(Kind::Operator, "*".into(), 2),
+                Token::new(Kind::Literal, "2".into(), 4),
+                Token::new(Kind::Unit, "s".into(), 6)
+            ]
+        );
+    }
 }
\ No newline at end of file
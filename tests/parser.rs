@@ -3,9 +3,10 @@ mod tests {
     #[cfg(test)]
     mod parser {
         use ralculator::expressions::lexer::Lexer;
-        use ralculator::expressions::parser::ast::Expression;
+        use ralculator::errors::diagnostic::{Diagnostic, Span};
+        use ralculator::expressions::parser::ast::{Expression, Numeral, Statement};
         use ralculator::expressions::parser::ast::Expression::{
-            Addition, Multiplication, ParenthesisExpression, UnaryMinus, Variable,
+            Addition, Call, Multiplication, ParenthesisExpression, Power, UnaryMinus, Variable,
         };
         use ralculator::expressions::parser::Parser;
 
@@ -15,17 +16,40 @@ mod tests {
             let mut parser = Parser::new();
             assert_eq!(
                 parser.parse(&lexer.lex("a = 1").unwrap()).unwrap(),
-                Expression::Assignment("a".into(), Box::new(Expression::Literal(1.0)))
+                Expression::Assignment("a".into(), Box::new(Expression::Literal(1.into())))
             )
         }
 
+        #[test]
+        fn parse_binding() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("a := 1").unwrap()).unwrap(),
+                Expression::Binding("a".into(), Box::new(Expression::Literal(1.into())))
+            );
+            // The variable is known afterwards, like with =
+            assert_eq!(
+                parser.parse(&lexer.lex("b := a + 1").unwrap()).unwrap(),
+                Expression::Binding(
+                    "b".into(),
+                    Box::new(Addition(
+                        Box::new(Variable("a".into(), Span::new(5, 6))),
+                        Box::new(Expression::Literal(1.into()))
+                    ))
+                )
+            );
+            assert!(parser.parse(&lexer.lex("1 := 2").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("f(x) := x").unwrap()).is_err());
+        }
+
         #[test]
         fn parse_1() {
             let lexer = Lexer::new();
             let mut parser = Parser::new();
             assert_eq!(
                 parser.parse(&lexer.lex("1").unwrap()).unwrap(),
-                Expression::Literal(1f64)
+                Expression::Literal(1.into())
             )
         }
 
@@ -35,7 +59,7 @@ mod tests {
             let mut parser = Parser::new();
             assert_eq!(
                 parser.parse(&lexer.lex("23").unwrap()).unwrap(),
-                Expression::Literal(23f64)
+                Expression::Literal(23.into())
             )
         }
 
@@ -45,7 +69,7 @@ mod tests {
             let mut parser = Parser::new();
             assert_eq!(
                 parser.parse(&lexer.lex("-1").unwrap()).unwrap(),
-                UnaryMinus(Box::new(Expression::Literal(1f64)))
+                UnaryMinus(Box::new(Expression::Literal(1.into())))
             )
         }
 
@@ -56,8 +80,8 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("1 + 1").unwrap()).unwrap(),
                 Addition(
-                    Box::new(Expression::Literal(1f64)),
-                    Box::new(Expression::Literal(1f64))
+                    Box::new(Expression::Literal(1.into())),
+                    Box::new(Expression::Literal(1.into()))
                 )
             );
         }
@@ -69,8 +93,8 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("1 - 2").unwrap()).unwrap(),
                 Expression::Subtraction(
-                    Box::new(Expression::Literal(1f64)),
-                    Box::new(Expression::Literal(2f64))
+                    Box::new(Expression::Literal(1.into())),
+                    Box::new(Expression::Literal(2.into()))
                 )
             );
         }
@@ -82,8 +106,8 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("1 * 4").unwrap()).unwrap(),
                 Multiplication(
-                    Box::new(Expression::Literal(1f64)),
-                    Box::new(Expression::Literal(4f64))
+                    Box::new(Expression::Literal(1.into())),
+                    Box::new(Expression::Literal(4.into()))
                 )
             );
         }
@@ -95,10 +119,10 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("1 * (3 + 4)").unwrap()).unwrap(),
                 Multiplication(
-                    Box::new(Expression::Literal(1f64)),
+                    Box::new(Expression::Literal(1.into())),
                     Box::new(ParenthesisExpression(Box::new(Addition(
-                        Box::new(Expression::Literal(3f64)),
-                        Box::new(Expression::Literal(4f64))
+                        Box::new(Expression::Literal(3.into())),
+                        Box::new(Expression::Literal(4.into()))
                     ))))
                 )
             );
@@ -111,10 +135,10 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("1*(3+4)").unwrap()).unwrap(),
                 Multiplication(
-                    Box::new(Expression::Literal(1f64)),
+                    Box::new(Expression::Literal(1.into())),
                     Box::new(ParenthesisExpression(Box::new(Addition(
-                        Box::new(Expression::Literal(3f64)),
-                        Box::new(Expression::Literal(4f64))
+                        Box::new(Expression::Literal(3.into())),
+                        Box::new(Expression::Literal(4.into()))
                     ))))
                 )
             );
@@ -128,12 +152,22 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("1 + a").unwrap()).unwrap(),
                 Addition(
-                    Box::new(Expression::Literal(1f64)),
-                    Box::new(Variable(String::from("a")))
+                    Box::new(Expression::Literal(1.into())),
+                    Box::new(Variable(String::from("a"), Span::new(4, 5)))
                 )
             );
         }
 
+        #[test]
+        fn parse_failing_assignment_defines_nothing() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert!(parser.parse(&lexer.lex("a = 1 +").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("b := 2 3").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("a").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("b").unwrap()).is_err());
+        }
+
         #[test]
         fn parse_undefined_variable() {
             let lexer = Lexer::new();
@@ -149,10 +183,10 @@ mod tests {
                 parser.parse(&lexer.lex("(1 +  2) *  3").unwrap()).unwrap(),
                 Multiplication(
                     Box::new(ParenthesisExpression(Box::new(Addition(
-                        Box::new(Expression::Literal(1f64)),
-                        Box::new(Expression::Literal(2f64))
+                        Box::new(Expression::Literal(1.into())),
+                        Box::new(Expression::Literal(2.into()))
                     )))),
-                    Box::new(Expression::Literal(3f64))
+                    Box::new(Expression::Literal(3.into()))
                 )
             );
         }
@@ -164,10 +198,10 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("1 + (2 *  3)").unwrap()).unwrap(),
                 Addition(
-                    Box::new(Expression::Literal(1f64)),
+                    Box::new(Expression::Literal(1.into())),
                     Box::new(ParenthesisExpression(Box::new(Multiplication(
-                        Box::new(Expression::Literal(2f64)),
-                        Box::new(Expression::Literal(3f64))
+                        Box::new(Expression::Literal(2.into())),
+                        Box::new(Expression::Literal(3.into()))
                     ))))
                 )
             );
@@ -180,8 +214,8 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("-1 - -1").unwrap()).unwrap(),
                 Expression::Subtraction(
-                    Box::new(UnaryMinus(Box::new(Expression::Literal(1f64)))),
-                    Box::new(UnaryMinus(Box::new(Expression::Literal(1f64))))
+                    Box::new(UnaryMinus(Box::new(Expression::Literal(1.into())))),
+                    Box::new(UnaryMinus(Box::new(Expression::Literal(1.into()))))
                 )
             )
         }
@@ -193,10 +227,10 @@ mod tests {
             assert_eq!(
                 parser.parse(&lexer.lex("1 + (-2 *  3)").unwrap()).unwrap(),
                 Addition(
-                    Box::new(Expression::Literal(1f64)),
+                    Box::new(Expression::Literal(1.into())),
                     Box::new(ParenthesisExpression(Box::new(Multiplication(
-                        Box::new(UnaryMinus(Box::new(Expression::Literal(2f64)))),
-                        Box::new(Expression::Literal(3f64))
+                        Box::new(UnaryMinus(Box::new(Expression::Literal(2.into())))),
+                        Box::new(Expression::Literal(3.into()))
                     ))))
                 )
             );
@@ -227,14 +261,267 @@ mod tests {
         fn shall_pass() {
             let lexer = Lexer::new();
             let mut parser = Parser::new();
-            assert_eq!(parser.parse(&lexer.lex("2(2)").unwrap()).unwrap(), Expression::Multiplication(Box::from(Expression::Literal(2f64)), Box::from(Expression::Literal(2f64))));
+            assert_eq!(parser.parse(&lexer.lex("2(2)").unwrap()).unwrap(), Expression::Multiplication(Box::from(Expression::Literal(2.into())), Box::from(Expression::Literal(2.into()))));
         }
 
         #[test]
         fn shall_pass_too() {
             let lexer = Lexer::new();
             let mut parser = Parser::new();
-            assert_eq!(parser.parse(&lexer.lex("1+(1)").unwrap()).unwrap(), Expression::Addition(Box::from(Expression::Literal(1f64)), Box::from(Expression::ParenthesisExpression(Box::from(Expression::Literal(1f64))))))
+            assert_eq!(parser.parse(&lexer.lex("1+(1)").unwrap()).unwrap(), Expression::Addition(Box::from(Expression::Literal(1.into())), Box::from(Expression::ParenthesisExpression(Box::from(Expression::Literal(1.into()))))))
+        }
+
+        #[test]
+        fn parse_power_is_right_associative() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("2^3^2").unwrap()).unwrap(),
+                Power(
+                    Box::new(Expression::Literal(2.into())),
+                    Box::new(Power(
+                        Box::new(Expression::Literal(3.into())),
+                        Box::new(Expression::Literal(2.into())),
+                        Span::new(2, 5)
+                    )),
+                    Span::new(0, 5)
+                )
+            );
+        }
+
+        #[test]
+        fn parse_power_binds_tighter_than_unary_minus() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("-2^2 * 3").unwrap()).unwrap(),
+                Multiplication(
+                    Box::new(UnaryMinus(Box::new(Power(
+                        Box::new(Expression::Literal(2.into())),
+                        Box::new(Expression::Literal(2.into())),
+                        Span::new(1, 4)
+                    )))),
+                    Box::new(Expression::Literal(3.into()))
+                )
+            );
+        }
+
+        #[test]
+        fn parse_power_with_negative_exponent() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("2^-1").unwrap()).unwrap(),
+                Power(
+                    Box::new(Expression::Literal(2.into())),
+                    Box::new(UnaryMinus(Box::new(Expression::Literal(1.into())))),
+                    Span::new(0, 4)
+                )
+            );
+        }
+
+        #[test]
+        fn parse_implicit_multiplication_with_expression() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("2(1 + 1)").unwrap()).unwrap(),
+                Multiplication(
+                    Box::new(Expression::Literal(2.into())),
+                    Box::new(Addition(
+                        Box::new(Expression::Literal(1.into())),
+                        Box::new(Expression::Literal(1.into()))
+                    ))
+                )
+            );
+        }
+
+        #[test]
+        fn parse_trailing_tokens_fail() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert!(parser.parse(&lexer.lex("1 2").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("(1))").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("1 = 2").unwrap()).is_err());
+        }
+
+        #[test]
+        fn parse_call() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("2 * max(1, 2 + 3)").unwrap()).unwrap(),
+                Multiplication(
+                    Box::new(Expression::Literal(2.into())),
+                    Box::new(Call(
+                        String::from("max"),
+                        vec![
+                            Expression::Literal(1.into()),
+                            Addition(
+                                Box::new(Expression::Literal(2.into())),
+                                Box::new(Expression::Literal(3.into()))
+                            )
+                        ],
+                        Span::new(4, 17)
+                    ))
+                )
+            );
+        }
+
+        #[test]
+        fn parse_call_fail() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert!(parser.parse(&lexer.lex("nope(1)").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("max(1,)").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("max(1 2)").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("sqrt(4").unwrap()).is_err());
+        }
+
+        #[test]
+        fn parse_function_definition() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("f(x, y) = x^2 + y").unwrap()).unwrap(),
+                Expression::FunctionDefinition(
+                    String::from("f"),
+                    vec![String::from("x"), String::from("y")],
+                    Box::new(Addition(
+                        Box::new(Power(
+                            Box::new(Variable(String::from("x"), Span::new(10, 11))),
+                            Box::new(Expression::Literal(2.into())),
+                            Span::new(10, 13)
+                        )),
+                        Box::new(Variable(String::from("y"), Span::new(16, 17)))
+                    ))
+                )
+            );
+            assert_eq!(
+                parser.parse(&lexer.lex("f(1, 2)").unwrap()).unwrap(),
+                Call(
+                    String::from("f"),
+                    vec![Expression::Literal(1.into()), Expression::Literal(2.into())],
+                    Span::new(0, 7)
+                )
+            );
+            assert!(parser.parse(&lexer.lex("x").unwrap()).is_err()); // Parameters are local to the function
+        }
+
+        #[test]
+        fn parse_function_definition_fail() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert!(parser.parse(&lexer.lex("f(x, x) = x").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("f(x y) = x").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("f(x) = y").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("g(x) = g(x) 1").unwrap()).is_err());
+            // Failing definitions define nothing
+            assert!(parser.parse(&lexer.lex("f(1)").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("g(1)").unwrap()).is_err());
+            // Functions can still call themselves
+            assert!(parser.parse(&lexer.lex("h(n) = h(n - 1)").unwrap()).is_ok());
+            assert!(parser.parse(&lexer.lex("h(1)").unwrap()).is_ok());
+        }
+
+        #[test]
+        fn parse_constants() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            parser.parse(&lexer.lex("r = 2").unwrap()).unwrap();
+            assert_eq!(
+                parser.parse(&lexer.lex("2 * pi * r").unwrap()).unwrap(),
+                Multiplication(
+                    Box::new(Multiplication(
+                        Box::new(Expression::Literal(2.into())),
+                        Box::new(Variable(String::from("pi"), Span::new(4, 6)))
+                    )),
+                    Box::new(Variable(String::from("r"), Span::new(9, 10)))
+                )
+            );
+        }
+
+        #[test]
+        fn parse_imaginary_literal() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("3 - 4i").unwrap()).unwrap(),
+                Expression::Subtraction(
+                    Box::new(Expression::Literal(3.into())),
+                    Box::new(Expression::Literal(Numeral::imaginary(4)))
+                )
+            );
+        }
+
+        #[test]
+        fn parse_quantities_and_conversions() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("-2^3 km to m").unwrap()).unwrap(),
+                Expression::Conversion(
+                    Box::new(UnaryMinus(Box::new(Expression::Quantity(
+                        Box::new(Power(
+                            Box::new(Expression::Literal(2.into())),
+                            Box::new(Expression::Literal(3.into())),
+                            Span::new(1, 4)
+                        )),
+                        "km".parse().unwrap()
+                    )))),
+                    "m".parse().unwrap()
+                )
+            );
+            assert!(parser.parse(&lexer.lex("1 km to").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("to m").unwrap()).is_err());
+        }
+
+        #[test]
+        fn parse_programs() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            assert_eq!(
+                parser.parse(&lexer.lex("a = 1;; a\n\n2").unwrap()).unwrap(),
+                Expression::Program(vec![
+                    Statement {
+                        expression: Expression::Assignment("a".into(), Box::new(Expression::Literal(1.into()))),
+                        span: Span::new(0, 5),
+                    },
+                    Statement { expression: Variable("a".into(), Span::new(8, 9)), span: Span::new(8, 9) },
+                    Statement { expression: Expression::Literal(2.into()), span: Span::new(11, 12) },
+                ])
+            );
+            // A single statement is not wrapped in a program
+            assert_eq!(parser.parse(&lexer.lex("a;").unwrap()).unwrap(), Variable("a".into(), Span::new(0, 1)));
+            assert_eq!(parser.parse(&lexer.lex(";\n;").unwrap()).unwrap(), Expression::Eof);
+            // Names are known from the statement defining them on
+            assert!(parser.parse(&lexer.lex("b + 1; b = 2").unwrap()).is_err());
+            assert!(parser.parse(&lexer.lex("c = 2; g(x) = x * c; g(c)").unwrap()).is_ok());
+            let err = parser.parse(&lexer.lex("1; (2;)").unwrap()).unwrap_err();
+            assert_eq!(err.span(), Some(Span::new(5, 5)));
+        }
+
+        #[test]
+        fn display_round_trips() {
+            let lexer = Lexer::new();
+            let mut parser = Parser::new();
+            for source in [
+                "x = 2",
+                "a = 1 - (2 - 3) * -x^2",
+                "f(x, y) = max(x, y) / (1 + x)",
+                "2^3^2 + (2^3)^2",
+                "-2^3 km to m",
+                "0.0015 + 3 / 4 - 2.5i",
+                "a / (a * 2)",
+                "a = 2; a + 1",
+                "b := a * 2",
+            ] {
+                let ast = parser.parse(&lexer.lex(source).unwrap()).unwrap();
+                assert_eq!(ast.to_string(), source);
+                assert_eq!(parser.parse(&lexer.lex(&ast.to_string()).unwrap()).unwrap(), ast);
+            }
+            let ast = parser.parse(&lexer.lex("1.5e-3 * 2(a + 1)").unwrap()).unwrap();
+            assert_eq!(ast.to_string(), "0.0015 * 2 * (a + 1)");
         }
 
         #[test]
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use ralculator::expressions::interpreter::Interpreter;
+    use ralculator::expressions::lexer::Lexer;
+    use ralculator::expressions::parser::Parser;
+    use ralculator::interface::repl::Repl;
+
+    // Runs a session over the given lines, returning what was written to the output and to the errors.
+    fn session(input: &str) -> (String, String) {
+        let mut repl = Repl::new(Lexer::new(), Parser::new(), Interpreter::new());
+        let (mut output, mut errors) = (Vec::new(), Vec::new());
+        repl.session(input.as_bytes(), &mut output, &mut errors).unwrap();
+        (String::from_utf8(output).unwrap(), String::from_utf8(errors).unwrap())
+    }
+
+    #[test]
+    fn errors_dont_end_the_session() {
+        let (output, errors) = session("a = 2\n1 / 0\n1 $ 2\n1 +\nb + 1\na * 3\n");
+        assert_eq!(output, "> = 2\n> > > > > = 6\n> ");
+        assert_eq!(errors.matches("error[").count(), 4);
+        assert!(errors.contains("error[E0203]"));
+        assert!(errors.contains("error[E0001]"));
+        assert!(errors.contains("error[E0102]"));
+        assert!(errors.contains("error[E0103]"));
+    }
+
+    #[test]
+    fn failing_lines_have_no_effect() {
+        let (output, errors) = session("f(x) = x + 1\nf = 2 +\nf(1)\nb = 1 / 0\nb = 3\nb\n");
+        assert_eq!(output, "> f(x) defined\n> > = 2\n> > = 3\n> = 3\n> ");
+        assert_eq!(errors.matches("error[").count(), 2);
+    }
+
+    #[test]
+    fn session_ends_on_an_empty_line_or_the_end_of_the_input() {
+        assert_eq!(session("1 + 1\n\n2 + 2\n").0, "> = 2\n> ");
+        assert_eq!(session("1 + 1").0, "> = 2\n> ");
+        assert_eq!(session("").0, "> ");
+    }
+
+    #[test]
+    fn long_sessions_dont_grow_the_stack() {
+        let input = "x = 1\n".repeat(100_000);
+        let (output, errors) = session(&input);
+        assert_eq!(output.matches("= 1").count(), 100_000);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn history_is_kept_in_the_config_directory() {
+        if let Some(path) = Repl::history_path() {
+            assert!(path.ends_with("ralculator/history"));
+        }
+    }
+
+    #[test]
+    fn each_statement_is_reported() {
+        let (output, errors) = session("a = 2; f(x) = x * a; f(3)\nb = 1; 1 / 0\nb\n");
+        assert_eq!(output, "> = 2\nf(x) defined\n= 6\n> = 1\n> = 1\n> ");
+        assert!(errors.contains("error[E0203]: Cannot divide by 0.\n --> 1:8\n"));
+        assert_eq!(errors.matches("error[").count(), 1);
+        // A failing assignment still defines its variable, as with -e and scripts
+        let (_, errors) = session("c = 1 / 0\nc\n");
+        assert_eq!(errors.matches("error[E0203]: Cannot divide by 0.").count(), 2);
+    }
+
+    #[test]
+    fn open_parentheses_continue_on_the_next_line() {
+        let (output, errors) = session("max(1,\n\n  5,\n 3) + 1\n2 +\n(1 + \n");
+        assert_eq!(output, "> ... ... ... = 6\n> > ... ");
+        // Only parentheses continue the input, and the unfinished input is still reported
+        assert!(errors.contains("error[E0102]: Expected a value, got nothing\n --> 1:4\n"));
+        assert!(errors.contains("error[E0102]: Expected a value, got nothing\n --> 1:5\n"));
+        assert_eq!(errors.matches("error[").count(), 2);
+    }
+
+    #[test]
+    fn vars_lists_definitions_and_values() {
+        let (output, _) = session(":vars\na = 2\nb = a * 3 + 1\nf(x) = x^2\n:vars\n");
+        assert_eq!(
+            output,
+            "> Nothing defined yet\n> = 2\n> = 7\n> f(x) defined\n> a = 2 = 2\nb = a * 3 + 1 = 7\nf(x) = x^2\n> "
+        );
+    }
+
+    #[test]
+    fn vars_tells_frozen_bindings_apart() {
+        let (output, _) = session("n = 1\nstart := n + 1\nn = 5\n:vars\n");
+        assert!(output.ends_with("> n = 5 = 5\nstart := 2 (frozen)\n> "));
+    }
+
+    #[test]
+    fn del_and_clear_forget_names() {
+        let (output, errors) = session("a = 2\nb = a + 1\n:del a c\nb\na\n:vars\n:clear\nb\n:vars\n");
+        assert_eq!(
+            output,
+            "> = 2\n> = 3\n> a deleted\n> > > b = a + 1 (error: Variable a not found)\n\
+             > Every variable and function deleted\n> > Nothing defined yet\n> "
+        );
+        assert!(errors.contains("error: c is not defined"));
+        assert!(errors.contains("error[E0204]: Variable a not found"));
+        // The parser forgot them too
+        assert!(errors.contains("error[E0103]: Couldn't find symbol a"));
+        assert!(errors.contains("error[E0103]: Couldn't find symbol b"));
+    }
+
+    #[test]
+    fn ast_and_tokens_dont_evaluate() {
+        let (output, errors) = session(":tokens a = 1\n:ast a = 1\na\n");
+        assert!(output.starts_with("> Identifier a          0..1\nOperator   =          2..3\nLiteral    1          4..5\n"));
+        assert!(output.contains("> a =\n└── 1\n"));
+        assert!(errors.contains("error[E0103]: Couldn't find symbol a"));
+    }
+
+    #[test]
+    fn unknown_commands_are_reported() {
+        let (output, errors) = session(":help\n:nope\n:del\n");
+        assert!(output.contains(":vars"));
+        assert!(errors.contains("error: unknown command :nope, see :help"));
+        assert!(errors.contains("error: usage: :del name..."));
+    }
+
+    #[test]
+    fn deps_and_dependents_are_trees() {
+        let input = "r = 2; h = 3\narea(x) = pi * x^2\nbase = area(r)\nvolume = base * h\n\
+                     :deps volume\n:dependents r\n:deps zz\n:deps\n";
+        let (output, errors) = session(input);
+        assert!(output.contains(
+            "> volume\n├── base\n│   ├── area\n│   └── r\n└── h\n> r\n└── base\n    └── volume\n"
+        ));
+        assert!(errors.contains("error: zz is not defined"));
+        assert!(errors.contains("error: usage: :deps name"));
+        // Recursive functions are only walked once
+        let (output, _) = session("f(n) = f(n - 1)\n:deps f\n");
+        assert!(output.ends_with("> f\n└── f (cycle)\n> "));
+    }
+
+    #[test]
+    fn dot_exports_the_dependency_graph() {
+        let (output, _) = session("a = 1\nf(x) = x + a\nb = f(2)\n:dot\n");
+        assert!(output.ends_with(
+            "> digraph dependencies {\n    \"a\";\n    \"b\";\n    \"f\" [shape=box, label=\"f(x)\"];\n    \
+             \"b\" -> \"f\";\n    \"f\" -> \"a\";\n}\n> "
+        ));
+        let path = std::env::temp_dir().join(format!("ralculator-{}.dot", std::process::id()));
+        let (output, _) = session(&format!("a = 1\n:dot {}\n", path.display()));
+        assert!(output.contains("Dependency graph written to"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "digraph dependencies {\n    \"a\";\n}\n");
+        std::fs::remove_file(path).unwrap();
+    }
+}
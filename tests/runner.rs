@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use ralculator::errors::diagnostic::{Diagnostic, Span};
+    use ralculator::errors::Error;
+    use ralculator::expressions::interpreter::Interpreter;
+    use ralculator::expressions::lexer::Lexer;
+    use ralculator::expressions::parser::Parser;
+    use ralculator::interface::runner::{self, Outcome};
+
+    // Runs the source in a new session, returning the outcome of its statements and the interpreter.
+    fn run(source: &str, keep_going: bool) -> (Vec<Outcome<'_>>, Interpreter) {
+        let (lexer, mut parser, mut interpreter) = (Lexer::new(), Parser::new(), Interpreter::new());
+        let outcomes = runner::run(source, (&lexer, &mut parser, &mut interpreter), keep_going);
+        (outcomes, interpreter)
+    }
+
+    #[test]
+    fn statements_see_what_the_previous_ones_define() {
+        let (outcomes, _) = run("a = 2; f(x) = x * a # double\nf(3)", false);
+        let inputs = outcomes.iter().map(|outcome| outcome.input).collect::<Vec<_>>();
+        assert_eq!(inputs, ["a = 2", "f(x) = x * a", "f(3)"]);
+        assert_eq!(outcomes[1].definition.as_deref(), Some("f(x)"));
+        assert_eq!(outcomes[1].record.variable.as_deref(), Some("f"));
+        assert_eq!(*outcomes[2].result.as_ref().unwrap(), 6.0);
+    }
+
+    #[test]
+    fn failures_keep_what_was_defined_before_them() {
+        let (outcomes, mut interpreter) = run("a = 1; b = 1 / 0; c = 2 +; d = 3", false);
+        assert_eq!(outcomes.len(), 2);
+        let err = outcomes[1].result.as_ref().unwrap_err();
+        assert!(matches!(err, Error::Interpreter(_)));
+        assert_eq!(err.span(), Some(Span::new(11, 16)));
+        assert_eq!(interpreter.value("a").unwrap(), 1.0);
+        // The failing assignment still defines its variable
+        assert!(interpreter.value("b").is_err());
+        assert!(interpreter.variables().iter().any(|(name, _)| *name == "b"));
+
+        let (outcomes, mut interpreter) = run("a = 1; b = 1 / 0; c = 2 +; d = 3", true);
+        assert_eq!(outcomes.len(), 4);
+        assert!(matches!(outcomes[2].result, Err(Error::Syntax(_))));
+        assert_eq!(outcomes[2].input, "c = 2 +");
+        assert_eq!(interpreter.value("d").unwrap(), 3.0);
+    }
+
+    #[test]
+    fn sources_that_cant_be_lexed_fail_as_a_whole() {
+        let (outcomes, _) = run(" a = 1; b = $ ", true);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].input, "a = 1; b = $");
+        assert!(matches!(outcomes[0].result, Err(Error::Lexer(_))));
+    }
+}
@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use ralculator::errors::diagnostic::Diagnostic;
+    use ralculator::errors::Error;
+    use ralculator::expressions::interpreter::Interpreter;
+    use ralculator::expressions::lexer::Lexer;
+    use ralculator::expressions::parser::Parser;
+    use ralculator::interface::script::{Failure, Script};
+
+    // Runs the source as the file test.calc, returning the failures and what was written to the output and to
+    // the errors.
+    fn run(source: &str, keep_going: bool) -> (Vec<Failure>, String, String) {
+        run_as(source, keep_going, false)
+    }
+
+    fn run_as(source: &str, keep_going: bool, json: bool) -> (Vec<Failure>, String, String) {
+        let mut script = Script::new(Lexer::new(), Parser::new(), Interpreter::new());
+        script.set_keep_going(keep_going);
+        script.set_json(json);
+        let (mut output, mut errors) = (Vec::new(), Vec::new());
+        let failures = script.run("test.calc", source, &mut output, &mut errors).unwrap();
+        (failures, String::from_utf8(output).unwrap(), String::from_utf8(errors).unwrap())
+    }
+
+    #[test]
+    fn only_bare_expressions_are_printed() {
+        let (failures, output, errors) = run("# A comment\nx = 3 # three\nf(a) = a * x\n\nf(2); x + 1\n", false);
+        assert!(failures.is_empty());
+        assert_eq!(output, "6\n4\n");
+        assert_eq!(errors, "");
+    }
+
+    #[test]
+    fn statements_continue_within_parentheses() {
+        let (failures, output, _) = run("y = (1 +\n  2)\nmax(y,\n  # in between\n  5)\n", false);
+        assert!(failures.is_empty());
+        assert_eq!(output, "5\n");
+    }
+
+    #[test]
+    fn errors_are_reported_with_their_position_in_the_file() {
+        let (failures, output, errors) = run("1\n2 + 3\n  4 / zz\n5\n", false);
+        assert_eq!(output, "1\n5\n");
+        assert_eq!(failures.len(), 1);
+        assert_eq!((failures[0].line, failures[0].column), (3, 7));
+        assert!(matches!(failures[0].error, Error::Syntax(_)));
+        assert_eq!(failures[0].error.span().map(|span| span.start), Some(14));
+        assert_eq!(failures[0].to_string(), "test.calc:3:7: Couldn't find symbol zz");
+        assert!(errors.starts_with("error[E0103]: Couldn't find symbol zz\n --> test.calc:3:7\n"));
+        assert!(errors.contains("3 |   4 / zz\n"));
+    }
+
+    #[test]
+    fn statements_without_a_span_of_their_own_point_at_the_statement() {
+        let (failures, _, _) = run("1\na = 2; 1 / 0\n", false);
+        assert_eq!(failures[0].to_string(), "test.calc:2:8: Cannot divide by 0.");
+    }
+
+    #[test]
+    fn keeping_going_sums_the_failures_up() {
+        let (failures, output, errors) = run("1 / zz\n2 $ 3\n1 +\n4\n1 / 0; 5\n", true);
+        assert_eq!(output, "4\n5\n");
+        let failures = failures.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            failures,
+            [
+                "test.calc:1:5: Couldn't find symbol zz",
+                "test.calc:2:3: unknown token '$'",
+                "test.calc:3:4: Expected a value, got nothing",
+                "test.calc:5:1: Cannot divide by 0.",
+            ]
+        );
+        assert_eq!(errors.matches("error[").count(), 4);
+        assert!(errors.ends_with(&format!("error: 4 statements failed:\n  {}\n", failures.join("\n  "))));
+    }
+
+    #[test]
+    fn empty_scripts_succeed() {
+        let (failures, output, errors) = run("", false);
+        assert!(failures.is_empty());
+        assert_eq!((output.as_str(), errors.as_str()), ("", ""));
+        let (failures, output, _) = run("# nothing\n\n   \n", false);
+        assert!(failures.is_empty());
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn json_scripts_write_every_statement_and_error_to_the_output() {
+        let (failures, output, errors) = run_as("x = 2 # two
+x + 1
+1 / zz; 4
+", true, true);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(errors, "");
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], r#"{"input":"x = 2","value":"2","type":"float","variable":"x","error":null}"#);
+        assert!(lines[1].contains(r#""value":"3""#));
+        assert!(lines[2].starts_with(r#"{"input":"1 / zz","value":null"#));
+        assert!(lines[2].contains(r#""span":{"start":22,"end":24},"line":3,"column":5"#));
+        assert!(lines[3].contains(r#""value":"4""#));
+    }
+}